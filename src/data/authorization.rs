@@ -0,0 +1,360 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::data::{deserialize_pod_slice, discriminator, Deserialize, Serialize};
+
+/// Extra data passed on to the auth rules program when a rule set needs it
+/// (Amount, PubkeyMatch, ProgramOwned, MerkleProof, ...)
+pub struct AuthorizationData<'a> {
+    pub payload: Payload<'a>,
+}
+
+impl<'a> Deserialize<'a> for AuthorizationData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (payload, len) = Payload::deserialize_from(bytes)?;
+        Ok((Self { payload }, len))
+    }
+}
+
+impl<'a> Serialize for AuthorizationData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        self.payload.serialize_to(buffer)
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.payload.serialized_len()
+    }
+}
+
+/// On chain this is a `HashMap<String, PayloadType>`, but without an allocator we just borrow
+/// a slice of entries. If a key shows up more than once the last entry wins, like in a HashMap.
+///
+/// A payload decoded from instruction data can't be turned back into entries without allocating,
+/// so it is kept as it was encoded instead.
+pub enum Payload<'a> {
+    Entries(&'a [(PayloadKey, PayloadType<'a>)]),
+    Encoded(EncodedPayload<'a>),
+}
+
+impl<'a> Payload<'a> {
+    /// The value the program will see for this key
+    pub fn get(&self, key: PayloadKey) -> Option<PayloadValue<'a>> {
+        match self {
+            Self::Entries(map) => map
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value.as_value()),
+            Self::Encoded(encoded) => encoded.get(key.as_str()),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for Payload<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (encoded, len) = EncodedPayload::deserialize_from(bytes)?;
+        Ok((Self::Encoded(encoded), len))
+    }
+}
+
+impl<'a> Serialize for Payload<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let map = match self {
+            Self::Entries(map) => map,
+            Self::Encoded(encoded) => return encoded.serialize_to(buffer),
+        };
+
+        // borsh writes maps with their entries sorted by key, so go through the keys in order
+        // and only write the last value of each one
+        let mut offset = 4;
+        let mut len: u32 = 0;
+
+        for key in PayloadKey::SORTED {
+            if let Some((_, value)) = map.iter().rev().find(|(k, _)| *k == key) {
+                offset += key.as_str().serialize_to(&mut buffer[offset..]);
+                offset += value.serialize_to(&mut buffer[offset..]);
+                len += 1;
+            }
+        }
+
+        len.serialize_to(buffer);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        let map = match self {
+            Self::Entries(map) => map,
+            Self::Encoded(encoded) => return encoded.serialized_len(),
+        };
+
+        let mut len = 4;
+
+        for key in PayloadKey::SORTED {
+            if let Some((_, value)) = map.iter().rev().find(|(k, _)| *k == key) {
+                len += key.as_str().serialized_len() + value.serialized_len();
+            }
+        }
+
+        len
+    }
+}
+
+/// A borsh encoded payload, borrowed from instruction data.
+/// Every entry was checked when decoding, so reading them can't fail.
+#[derive(Clone, Copy)]
+pub struct EncodedPayload<'a> {
+    /// Including the u32 number of entries
+    bytes: &'a [u8],
+}
+
+impl<'a> EncodedPayload<'a> {
+    /// Iterates over the entries in the order they were encoded. Keys aren't limited to
+    /// `PayloadKey`, any string is accepted by the program
+    pub fn iter(&self) -> EncodedPayloadIter<'a> {
+        EncodedPayloadIter {
+            remaining: u32::from_le_bytes(self.bytes[..4].try_into().unwrap()),
+            bytes: &self.bytes[4..],
+        }
+    }
+
+    /// Last value for the key, like a HashMap
+    pub fn get(&self, key: &str) -> Option<PayloadValue<'a>> {
+        self.iter()
+            .filter(|(k, _)| *k == key)
+            .last()
+            .map(|(_, value)| value)
+    }
+
+    /// Reads a single `(String, PayloadType)` entry
+    fn read_entry(bytes: &'a [u8]) -> Result<((&'a str, PayloadValue<'a>), usize), ProgramError> {
+        let (key, key_len) = <&str>::deserialize_from(bytes)?;
+        let (value, value_len) = PayloadValue::deserialize_from(&bytes[key_len..])?;
+        Ok(((key, value), key_len + value_len))
+    }
+}
+
+impl<'a> Deserialize<'a> for EncodedPayload<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (len, mut offset) = u32::deserialize_from(bytes)?;
+
+        for _ in 0..len {
+            let (_, entry_len) = Self::read_entry(&bytes[offset..])?;
+            offset += entry_len;
+        }
+
+        Ok((
+            Self {
+                bytes: &bytes[..offset],
+            },
+            offset,
+        ))
+    }
+}
+
+impl<'a> Serialize for EncodedPayload<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[..self.bytes.len()].copy_from_slice(self.bytes);
+        self.bytes.len()
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+pub struct EncodedPayloadIter<'a> {
+    remaining: u32,
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for EncodedPayloadIter<'a> {
+    type Item = (&'a str, PayloadValue<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // checked when decoding
+        let (entry, len) = EncodedPayload::read_entry(self.bytes).ok()?;
+        self.remaining -= 1;
+        self.bytes = &self.bytes[len..];
+
+        Some(entry)
+    }
+}
+
+/// Keys the token metadata program knows how to fill in a payload
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PayloadKey {
+    Amount,
+    Authority,
+    AuthoritySeeds,
+    Delegate,
+    DelegateSeeds,
+    Destination,
+    DestinationSeeds,
+    Holder,
+    Source,
+    SourceSeeds,
+}
+
+impl PayloadKey {
+    /// All keys, in the same order as their string representation
+    pub const SORTED: [PayloadKey; 10] = [
+        Self::Amount,
+        Self::Authority,
+        Self::AuthoritySeeds,
+        Self::Delegate,
+        Self::DelegateSeeds,
+        Self::Destination,
+        Self::DestinationSeeds,
+        Self::Holder,
+        Self::Source,
+        Self::SourceSeeds,
+    ];
+
+    /// The string used as the key of the payload map
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Amount => "Amount",
+            Self::Authority => "Authority",
+            Self::AuthoritySeeds => "AuthoritySeeds",
+            Self::Delegate => "Delegate",
+            Self::DelegateSeeds => "DelegateSeeds",
+            Self::Destination => "Destination",
+            Self::DestinationSeeds => "DestinationSeeds",
+            Self::Holder => "Holder",
+            Self::Source => "Source",
+            Self::SourceSeeds => "SourceSeeds",
+        }
+    }
+}
+
+pub enum PayloadType<'a> {
+    /// A plain `Pubkey`.
+    Pubkey(Pubkey),
+    /// PDA derivation seeds (a `Vec<Vec<u8>>`).
+    Seeds(&'a [&'a [u8]]),
+    /// A merkle proof (a `Vec<[u8; 32]>`).
+    MerkleProof(&'a [[u8; 32]]),
+    /// A plain `u64` used for `Amount`.
+    Number(u64),
+}
+
+impl<'a> PayloadType<'a> {
+    fn as_value(&self) -> PayloadValue<'a> {
+        match self {
+            Self::Pubkey(pubkey) => PayloadValue::Pubkey(*pubkey),
+            Self::Seeds(seeds) => PayloadValue::Seeds(Seeds::Slices(seeds.iter())),
+            Self::MerkleProof(proof) => PayloadValue::MerkleProof(proof),
+            Self::Number(num) => PayloadValue::Number(*num),
+        }
+    }
+}
+
+impl<'a> Serialize for PayloadType<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::Pubkey(pubkey) => {
+                buffer[0] = 0;
+                1 + pubkey.serialize_to(&mut buffer[1..])
+            }
+            Self::Seeds(seeds) => {
+                buffer[0] = 1;
+                1 + seeds.serialize_to(&mut buffer[1..])
+            }
+            Self::MerkleProof(proof) => {
+                buffer[0] = 2;
+                1 + proof.serialize_to(&mut buffer[1..])
+            }
+            Self::Number(num) => {
+                buffer[0] = 3;
+                1 + num.serialize_to(&mut buffer[1..])
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + match self {
+            Self::Pubkey(pubkey) => pubkey.serialized_len(),
+            Self::Seeds(seeds) => seeds.serialized_len(),
+            Self::MerkleProof(proof) => proof.serialized_len(),
+            Self::Number(num) => num.serialized_len(),
+        }
+    }
+}
+
+/// A `PayloadType` as read from a payload, no matter if it was built or decoded
+#[derive(Clone)]
+pub enum PayloadValue<'a> {
+    Pubkey(Pubkey),
+    Seeds(Seeds<'a>),
+    MerkleProof(&'a [[u8; 32]]),
+    Number(u64),
+}
+
+impl<'a> Deserialize<'a> for PayloadValue<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let kind = discriminator(bytes)?;
+        let data = &bytes[1..];
+
+        let (value, len) = match kind {
+            0 => {
+                let (pubkey, len) = Pubkey::deserialize_from(data)?;
+                (Self::Pubkey(pubkey), len)
+            }
+            1 => {
+                let (count, mut len) = u32::deserialize_from(data)?;
+                for _ in 0..count {
+                    len += <&[u8]>::deserialize_from(&data[len..])?.1;
+                }
+                let seeds = Seeds::Encoded {
+                    remaining: count,
+                    bytes: &data[4..len],
+                };
+                (Self::Seeds(seeds), len)
+            }
+            2 => {
+                let (proof, len) = deserialize_pod_slice(data)?;
+                (Self::MerkleProof(proof), len)
+            }
+            3 => {
+                let (num, len) = u64::deserialize_from(data)?;
+                (Self::Number(num), len)
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok((value, 1 + len))
+    }
+}
+
+/// Iterates over PDA seeds, either borrowed from a `PayloadType` or still encoded
+#[derive(Clone)]
+pub enum Seeds<'a> {
+    Slices(core::slice::Iter<'a, &'a [u8]>),
+    Encoded { remaining: u32, bytes: &'a [u8] },
+}
+
+impl<'a> Iterator for Seeds<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Slices(seeds) => seeds.next().copied(),
+            Self::Encoded { remaining, bytes } => {
+                if *remaining == 0 {
+                    return None;
+                }
+
+                // checked when decoding
+                let (seed, len) = <&[u8]>::deserialize_from(bytes).ok()?;
+                *remaining -= 1;
+                *bytes = &bytes[len..];
+
+                Some(seed)
+            }
+        }
+    }
+}
@@ -1,17 +1,48 @@
-use crate::{data::Serialize, Instructions};
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{Deserialize, Serialize},
+    Instructions,
+};
 
 pub struct BurnInstructionData {
     /// The amount of the token to burn
     pub amount: u64,
 }
 
+impl BurnInstructionData {
+    /// discriminator + args version + amount
+    pub const MAX_LEN: usize = 1 + 1 + 8;
+}
+
 impl Serialize for BurnInstructionData {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         buffer[0] = Instructions::Burn.to_u8();
         let mut offset = 1;
 
+        // instruction data is actually and enum so write the first byte
+        buffer[offset] = 0;
+        offset += 1;
+
         offset += self.amount.serialize_to(&mut buffer[offset..]);
 
-        return offset;
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::MAX_LEN
+    }
+}
+
+impl Deserialize<'_> for BurnInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        // discriminator and the only version of the args
+        if bytes.get(..2) != Some(&[Instructions::Burn.to_u8(), 0]) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (amount, len) = u64::deserialize_from(&bytes[2..])?;
+
+        Ok((Self { amount }, 2 + len))
     }
 }
@@ -1,8 +1,11 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
 use crate::{
-    data::{nft::Creator, Serialize},
+    data::{
+        deserialize_sized, discriminator, nft::Creator, read, update::Data, Deserialize,
+        DeserializeSized, Serialize,
+    },
     Instructions,
 };
 
@@ -18,7 +21,11 @@ impl Serialize for DataState {
             Self::AccountState => 0,
             Self::LedgerState => 1,
         };
-        return 1;
+        1
+    }
+
+    fn serialized_len(&self) -> usize {
+        1
     }
 }
 
@@ -31,6 +38,10 @@ pub enum PrintSupply {
     Unlimited,
 }
 
+impl PrintSupply {
+    pub const MAX_LEN: usize = 1 + 8;
+}
+
 impl Serialize for PrintSupply {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         match self {
@@ -49,8 +60,30 @@ impl Serialize for PrintSupply {
             }
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::Limited(_) => 1 + size_of::<u64>(),
+            Self::Zero | Self::Unlimited => 1,
+        }
+    }
 }
 
+impl Deserialize<'_> for PrintSupply {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((Self::Zero, 1)),
+            1 => {
+                let (num, len) = u64::deserialize_from(&bytes[1..])?;
+                Ok((Self::Limited(num), 1 + len))
+            }
+            2 => Ok((Self::Unlimited, 1)),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum TokenStandard {
     NonFungible,                    // This is a master edition
     FungibleAsset,                  // A token with metadata that can also have attributes
@@ -89,6 +122,30 @@ impl Serialize for TokenStandard {
             }
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl DeserializeSized for TokenStandard {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        match bytes.first() {
+            Some(0) => Ok(Self::NonFungible),
+            Some(1) => Ok(Self::FungibleAsset),
+            Some(2) => Ok(Self::Fungible),
+            Some(3) => Ok(Self::NonFungibleEdition),
+            Some(4) => Ok(Self::ProgrammableNonFungible),
+            Some(5) => Ok(Self::ProgrammableNonFungibleEdition),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+impl Deserialize<'_> for TokenStandard {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_sized(bytes, 1)
+    }
 }
 
 #[derive(Pod, Zeroable, Clone, Copy)]
@@ -98,6 +155,10 @@ pub struct Collection {
     pub key: Pubkey,
 }
 
+impl Collection {
+    pub const LEN: usize = size_of::<Self>();
+}
+
 impl Serialize for Collection {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         let mut offset = 0;
@@ -105,8 +166,28 @@ impl Serialize for Collection {
         offset += self.key.serialize_to(&mut buffer[offset..]);
         offset
     }
+
+    fn serialized_len(&self) -> usize {
+        Self::LEN
+    }
 }
 
+impl Deserialize<'_> for Collection {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        let (verified, _) = bool::deserialize_from(bytes)?;
+        let (key, _) = Pubkey::deserialize_from(&bytes[1..])?;
+
+        Ok((
+            Self {
+                verified: verified as u8,
+                key,
+            },
+            Self::LEN,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Uses {
     // 17 bytes + Option byte
     pub use_method: UseMethod, //1
@@ -114,6 +195,10 @@ pub struct Uses {
     pub total: u64,            //8
 }
 
+impl Uses {
+    pub const LEN: usize = 1 + 8 + 8;
+}
+
 impl Serialize for Uses {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         let mut offset = 0;
@@ -122,8 +207,29 @@ impl Serialize for Uses {
         offset += self.total.serialize_to(&mut buffer[offset..]);
         offset
     }
+
+    fn serialized_len(&self) -> usize {
+        Self::LEN
+    }
 }
 
+impl DeserializeSized for Uses {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        Ok(Self {
+            use_method: UseMethod::deserialize(bytes)?,
+            remaining: u64::deserialize(bytes.get(1..).ok_or(ProgramError::InvalidAccountData)?)?,
+            total: u64::deserialize(bytes.get(9..).ok_or(ProgramError::InvalidAccountData)?)?,
+        })
+    }
+}
+
+impl Deserialize<'_> for Uses {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_sized(bytes, Self::LEN)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum UseMethod {
     Burn,
     Multiple,
@@ -147,6 +253,27 @@ impl Serialize for UseMethod {
             }
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl DeserializeSized for UseMethod {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        match bytes.first() {
+            Some(0) => Ok(Self::Burn),
+            Some(1) => Ok(Self::Multiple),
+            Some(2) => Ok(Self::Single),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+impl Deserialize<'_> for UseMethod {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_sized(bytes, 1)
+    }
 }
 
 pub struct AssetData<'a> {
@@ -176,17 +303,33 @@ pub struct AssetData<'a> {
     pub rule_set: Option<Pubkey>,
 }
 
+impl<'a> AssetData<'a> {
+    /// Biggest asset the program accepts: every string at its max length, max creators and every option set
+    pub const MAX_LEN: usize = Data::MAX_LEN
+        + 1 // primary_sale_happened
+        + 1 // is_mutable
+        + 1 // token_standard
+        + (1 + Collection::LEN)
+        + (1 + Uses::LEN)
+        + (1 + CollectionDetails::LEN)
+        + (1 + 32); // rule_set
+
+    /// The fields it shares with `Data`, which encodes them
+    fn data(&self) -> Data<'a> {
+        Data {
+            name: self.name,
+            symbol: self.symbol,
+            uri: self.uri,
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            creators: self.creators,
+        }
+    }
+}
+
 impl<'a> Serialize for AssetData<'a> {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
-        let mut offset = 0;
+        let mut offset = self.data().serialize_to(buffer);
 
-        offset += self.name.serialize_to(&mut buffer[offset..]);
-        offset += self.symbol.serialize_to(&mut buffer[offset..]);
-        offset += self.uri.serialize_to(&mut buffer[offset..]);
-        offset += self
-            .seller_fee_basis_points
-            .serialize_to(&mut buffer[offset..]);
-        offset += self.creators.serialize_to(&mut buffer[offset..]);
         offset += self
             .primary_sale_happened
             .serialize_to(&mut buffer[offset..]);
@@ -197,15 +340,55 @@ impl<'a> Serialize for AssetData<'a> {
         offset += self.collection_details.serialize_to(&mut buffer[offset..]);
         offset += self.rule_set.serialize_to(&mut buffer[offset..]);
 
-        return offset;
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.data().serialized_len()
+            + self.primary_sale_happened.serialized_len()
+            + self.is_mutable.serialized_len()
+            + self.token_standard.serialized_len()
+            + self.collection.serialized_len()
+            + self.uses.serialized_len()
+            + self.collection_details.serialized_len()
+            + self.rule_set.serialized_len()
     }
 }
 
+impl<'a> Deserialize<'a> for AssetData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (data, mut offset) = Data::deserialize_from(bytes)?;
+
+        let asset_data = Self {
+            name: data.name,
+            symbol: data.symbol,
+            uri: data.uri,
+            seller_fee_basis_points: data.seller_fee_basis_points,
+            creators: data.creators,
+            primary_sale_happened: read(bytes, &mut offset)?,
+            is_mutable: read(bytes, &mut offset)?,
+            token_standard: read(bytes, &mut offset)?,
+            collection: read(bytes, &mut offset)?,
+            uses: read(bytes, &mut offset)?,
+            collection_details: read(bytes, &mut offset)?,
+            rule_set: read(bytes, &mut offset)?,
+        };
+
+        Ok((asset_data, offset))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum CollectionDetails {
     V1 { size: u64 },
     V2 { padding: [u8; 8] },
 }
 
+impl CollectionDetails {
+    /// Both versions have 8 bytes after the discriminator
+    pub const LEN: usize = 1 + 8;
+}
+
 impl Serialize for CollectionDetails {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         match self {
@@ -214,19 +397,52 @@ impl Serialize for CollectionDetails {
                 size.serialize_to(&mut buffer[1..1 + size_of::<u64>()]);
                 1 + size_of::<u64>()
             }
-            // I assume padding is useless
-            Self::V2 { padding: _ } => {
-                buffer[1] = 0;
+            Self::V2 { padding } => {
+                buffer[0] = 1;
+                buffer[1..1 + 8].copy_from_slice(padding);
                 1 + 8
             }
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        Self::LEN
+    }
+}
+
+impl DeserializeSized for CollectionDetails {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        let payload: [u8; 8] = bytes
+            .get(1..9)
+            .ok_or(ProgramError::InvalidAccountData)?
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        match bytes[0] {
+            0 => Ok(Self::V1 {
+                size: u64::from_le_bytes(payload),
+            }),
+            1 => Ok(Self::V2 { padding: payload }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+impl Deserialize<'_> for CollectionDetails {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_sized(bytes, Self::LEN)
+    }
 }
 
 pub struct CreateAssetInstructionData<'a> {
-    asset_data: AssetData<'a>,
-    decimals: Option<u8>,
-    print_supply: Option<PrintSupply>,
+    pub asset_data: AssetData<'a>,
+    pub decimals: Option<u8>,
+    pub print_supply: Option<PrintSupply>,
+}
+
+impl CreateAssetInstructionData<'_> {
+    /// discriminator + args version + asset data + decimals + print supply
+    pub const MAX_LEN: usize = 1 + 1 + AssetData::MAX_LEN + 2 + 1 + PrintSupply::MAX_LEN;
 }
 
 impl<'a> Serialize for CreateAssetInstructionData<'a> {
@@ -242,6 +458,38 @@ impl<'a> Serialize for CreateAssetInstructionData<'a> {
         offset += self.decimals.serialize_to(&mut buffer[offset..]);
         offset += self.print_supply.serialize_to(&mut buffer[offset..]);
 
-        return offset;
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        2 + self.asset_data.serialized_len()
+            + self.decimals.serialized_len()
+            + self.print_supply.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for CreateAssetInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        // discriminator and the only version of the args
+        if bytes.get(..2) != Some(&[Instructions::Create.to_u8(), 0]) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let mut offset = 2;
+
+        let (asset_data, len) = AssetData::deserialize_from(&bytes[offset..])?;
+        offset += len;
+        let (decimals, len) = Option::deserialize_from(&bytes[offset..])?;
+        offset += len;
+        let (print_supply, len) = Option::deserialize_from(&bytes[offset..])?;
+        offset += len;
+
+        Ok((
+            Self {
+                asset_data,
+                decimals,
+                print_supply,
+            },
+            offset,
+        ))
     }
 }
@@ -0,0 +1,43 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{Deserialize, Serialize},
+    Instructions,
+};
+
+pub struct CreateMasterEditionV3InstructionData {
+    /// Maximum number of editions that can be printed. None means unlimited
+    pub max_supply: Option<u64>,
+}
+
+impl CreateMasterEditionV3InstructionData {
+    /// discriminator + max supply
+    pub const MAX_LEN: usize = 1 + 1 + 8;
+}
+
+impl Serialize for CreateMasterEditionV3InstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::CreateMasterEditionV3.to_u8();
+        let mut offset = 1;
+
+        offset += self.max_supply.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.max_supply.serialized_len()
+    }
+}
+
+impl Deserialize<'_> for CreateMasterEditionV3InstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::CreateMasterEditionV3.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (max_supply, len) = Option::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { max_supply }, 1 + len))
+    }
+}
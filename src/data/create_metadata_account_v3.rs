@@ -0,0 +1,136 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{
+        create::{Collection, CollectionDetails, Uses},
+        nft::Creator,
+        read,
+        update::Data,
+        Deserialize, Serialize,
+    },
+    Instructions,
+};
+
+/// The data of a metadata account, as the legacy instructions take it
+pub struct DataV2<'a> {
+    /// The name of the asset.
+    pub name: &'a [u8],
+    /// The symbol for the asset.
+    pub symbol: &'a [u8],
+    /// URI pointing to JSON representing the asset.
+    pub uri: &'a [u8],
+    /// Royalty basis points that goes to creators in secondary sales (0-10000).
+    pub seller_fee_basis_points: u16,
+    /// Array of creators.
+    pub creators: Option<&'a [Creator]>,
+    /// Collection information.
+    pub collection: Option<Collection>,
+    /// Uses information.
+    pub uses: Option<Uses>,
+}
+
+impl<'a> DataV2<'a> {
+    /// Every string at its max length, max creators and every option set
+    pub const MAX_LEN: usize = Data::MAX_LEN + (1 + Collection::LEN) + (1 + Uses::LEN);
+
+    /// The fields it shares with `Data`, which encodes them
+    fn data(&self) -> Data<'a> {
+        Data {
+            name: self.name,
+            symbol: self.symbol,
+            uri: self.uri,
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            creators: self.creators,
+        }
+    }
+}
+
+impl<'a> Serialize for DataV2<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let mut offset = self.data().serialize_to(buffer);
+
+        offset += self.collection.serialize_to(&mut buffer[offset..]);
+        offset += self.uses.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.data().serialized_len() + self.collection.serialized_len() + self.uses.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for DataV2<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (data, mut offset) = Data::deserialize_from(bytes)?;
+
+        let data_v2 = Self {
+            name: data.name,
+            symbol: data.symbol,
+            uri: data.uri,
+            seller_fee_basis_points: data.seller_fee_basis_points,
+            creators: data.creators,
+            collection: read(bytes, &mut offset)?,
+            uses: read(bytes, &mut offset)?,
+        };
+
+        Ok((data_v2, offset))
+    }
+}
+
+pub struct CreateMetadataAccountV3InstructionData<'a> {
+    pub data: DataV2<'a>,
+    /// Whether or not the data struct is mutable (default is not).
+    pub is_mutable: bool,
+    /// Set it to make the asset a sized collection.
+    pub collection_details: Option<CollectionDetails>,
+}
+
+impl CreateMetadataAccountV3InstructionData<'_> {
+    /// discriminator + data + is_mutable + collection details
+    pub const MAX_LEN: usize = 1 + DataV2::MAX_LEN + 1 + (1 + CollectionDetails::LEN);
+}
+
+impl<'a> Serialize for CreateMetadataAccountV3InstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::CreateMetadataAccountV3.to_u8();
+        let mut offset = 1;
+
+        offset += self.data.serialize_to(&mut buffer[offset..]);
+        offset += self.is_mutable.serialize_to(&mut buffer[offset..]);
+        offset += self.collection_details.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.data.serialized_len()
+            + self.is_mutable.serialized_len()
+            + self.collection_details.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for CreateMetadataAccountV3InstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::CreateMetadataAccountV3.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let mut offset = 1;
+
+        let (data, len) = DataV2::deserialize_from(&bytes[offset..])?;
+        offset += len;
+        let (is_mutable, len) = bool::deserialize_from(&bytes[offset..])?;
+        offset += len;
+        let (collection_details, len) = Option::deserialize_from(&bytes[offset..])?;
+        offset += len;
+
+        Ok((
+            Self {
+                data,
+                is_mutable,
+                collection_details,
+            },
+            offset,
+        ))
+    }
+}
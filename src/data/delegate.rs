@@ -0,0 +1,277 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    data::{authorization::AuthorizationData, discriminator, read, Deserialize, Serialize},
+    Instructions,
+};
+
+pub enum DelegateArgs<'a> {
+    CollectionV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    SaleV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    TransferV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    DataV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    UtilityV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    StakingV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    StandardV1 {
+        amount: u64,
+    },
+    LockedTransferV1 {
+        amount: u64,
+        locked_address: Pubkey,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    ProgrammableConfigV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AuthorityItemV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    DataItemV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    CollectionItemV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    ProgrammableConfigItemV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    PrintDelegateV1 {
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+}
+
+impl<'a> Serialize for DelegateArgs<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let mut offset = 1;
+
+        match self {
+            Self::CollectionV1 { authorization_data } => {
+                buffer[0] = 0;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::SaleV1 {
+                amount,
+                authorization_data,
+            } => {
+                buffer[0] = 1;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::TransferV1 {
+                amount,
+                authorization_data,
+            } => {
+                buffer[0] = 2;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::DataV1 { authorization_data } => {
+                buffer[0] = 3;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::UtilityV1 {
+                amount,
+                authorization_data,
+            } => {
+                buffer[0] = 4;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::StakingV1 {
+                amount,
+                authorization_data,
+            } => {
+                buffer[0] = 5;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::StandardV1 { amount } => {
+                buffer[0] = 6;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+            }
+            Self::LockedTransferV1 {
+                amount,
+                locked_address,
+                authorization_data,
+            } => {
+                buffer[0] = 7;
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += locked_address.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::ProgrammableConfigV1 { authorization_data } => {
+                buffer[0] = 8;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AuthorityItemV1 { authorization_data } => {
+                buffer[0] = 9;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::DataItemV1 { authorization_data } => {
+                buffer[0] = 10;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::CollectionItemV1 { authorization_data } => {
+                buffer[0] = 11;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::ProgrammableConfigItemV1 { authorization_data } => {
+                buffer[0] = 12;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::PrintDelegateV1 { authorization_data } => {
+                buffer[0] = 13;
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+        }
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + match self {
+            Self::CollectionV1 { authorization_data }
+            | Self::DataV1 { authorization_data }
+            | Self::ProgrammableConfigV1 { authorization_data }
+            | Self::AuthorityItemV1 { authorization_data }
+            | Self::DataItemV1 { authorization_data }
+            | Self::CollectionItemV1 { authorization_data }
+            | Self::ProgrammableConfigItemV1 { authorization_data }
+            | Self::PrintDelegateV1 { authorization_data } => authorization_data.serialized_len(),
+            Self::SaleV1 {
+                amount,
+                authorization_data,
+            }
+            | Self::TransferV1 {
+                amount,
+                authorization_data,
+            }
+            | Self::UtilityV1 {
+                amount,
+                authorization_data,
+            }
+            | Self::StakingV1 {
+                amount,
+                authorization_data,
+            } => amount.serialized_len() + authorization_data.serialized_len(),
+            Self::StandardV1 { amount } => amount.serialized_len(),
+            Self::LockedTransferV1 {
+                amount,
+                locked_address,
+                authorization_data,
+            } => {
+                amount.serialized_len()
+                    + locked_address.serialized_len()
+                    + authorization_data.serialized_len()
+            }
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for DelegateArgs<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let mut offset = 1;
+
+        // fields are read in the order they are written, like borsh
+        let args = match discriminator(bytes)? {
+            0 => Self::CollectionV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            1 => Self::SaleV1 {
+                amount: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            2 => Self::TransferV1 {
+                amount: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            3 => Self::DataV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            4 => Self::UtilityV1 {
+                amount: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            5 => Self::StakingV1 {
+                amount: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            6 => Self::StandardV1 {
+                amount: read(bytes, &mut offset)?,
+            },
+            7 => Self::LockedTransferV1 {
+                amount: read(bytes, &mut offset)?,
+                locked_address: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            8 => Self::ProgrammableConfigV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            9 => Self::AuthorityItemV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            10 => Self::DataItemV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            11 => Self::CollectionItemV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            12 => Self::ProgrammableConfigItemV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            13 => Self::PrintDelegateV1 {
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok((args, offset))
+    }
+}
+
+pub struct DelegateInstructionData<'a> {
+    pub args: DelegateArgs<'a>,
+}
+
+impl<'a> Serialize for DelegateInstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Delegate.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for DelegateInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Delegate.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = DelegateArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
@@ -0,0 +1,170 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::data::{
+    nft::{check_owner, read_pubkey, Borrowed, Key},
+    DeserializeSized,
+};
+
+/// Zero copy view over a MasterEditionV1 or MasterEditionV2 account.
+///
+/// V1 is deprecated, but old master editions that were never converted still use it.
+/// The only difference is that V1 also has the printing mints after `max_supply`.
+pub struct MasterEditionView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MasterEditionView<'a> {
+    /// Checks the Key of the account
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        // key + supply + max_supply option byte
+        if bytes.len() < 1 + 8 + 1 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let view = Self { bytes };
+
+        match bytes[0] {
+            key if key == Key::MasterEditionV2 as u8 => Ok(view),
+            // V1 is bigger, make sure the printing mints are actually there
+            key if key == Key::MasterEditionV1 as u8 => {
+                if bytes.len() < view.printing_mint_offset()? + 32 + 32 {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                Ok(view)
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Checks the owner of the account, then the Key. The data stays borrowed
+    /// until the returned `Borrowed` is dropped
+    pub fn from_account_info(account: &'a AccountInfo) -> Result<Borrowed<'a, Self>, ProgramError> {
+        Borrowed::new(account, Self::new)
+    }
+
+    /// Checks the owner of the account, then the Key
+    ///
+    /// # Safety
+    ///
+    /// Borrows the account data without going through the runtime borrow flags,
+    /// so there can't be any mutable borrow of it while the view is alive.
+    pub unsafe fn from_account_info_unchecked(
+        account: &'a AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        check_owner(account)?;
+        Self::new(account.borrow_data_unchecked())
+    }
+
+    pub fn key(&self) -> Key {
+        if self.bytes[0] == Key::MasterEditionV1 as u8 {
+            Key::MasterEditionV1
+        } else {
+            Key::MasterEditionV2
+        }
+    }
+
+    /// How many editions have been printed
+    pub fn supply(&self) -> u64 {
+        // checked when creating the view
+        u64::from_le_bytes(self.bytes[1..9].try_into().unwrap())
+    }
+
+    /// Maximum number of editions that can be printed. None means unlimited
+    pub fn max_supply(&self) -> Result<Option<u64>, ProgramError> {
+        match self.bytes[9] {
+            0 => Ok(None),
+            1 => Ok(Some(u64::deserialize(
+                self.bytes
+                    .get(10..)
+                    .ok_or(ProgramError::InvalidAccountData)?,
+            )?)),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// How many editions can still be printed. None means unlimited
+    pub fn remaining_supply(&self) -> Result<Option<u64>, ProgramError> {
+        Ok(self
+            .max_supply()?
+            .map(|max_supply| max_supply.saturating_sub(self.supply())))
+    }
+
+    /// Only V1 has this
+    pub fn printing_mint(&self) -> Result<Option<&'a Pubkey>, ProgramError> {
+        if self.key() != Key::MasterEditionV1 {
+            return Ok(None);
+        }
+
+        let offset = self.printing_mint_offset()?;
+        Ok(Some(read_pubkey(self.bytes, offset)?))
+    }
+
+    /// Only V1 has this
+    pub fn one_time_printing_authorization_mint(&self) -> Result<Option<&'a Pubkey>, ProgramError> {
+        if self.key() != Key::MasterEditionV1 {
+            return Ok(None);
+        }
+
+        let offset = self.printing_mint_offset()? + 32;
+        Ok(Some(read_pubkey(self.bytes, offset)?))
+    }
+
+    fn printing_mint_offset(&self) -> Result<usize, ProgramError> {
+        // key + supply + Option<u64>
+        match self.bytes.get(9) {
+            Some(0) => Ok(1 + 8 + 1),
+            Some(1) => Ok(1 + 8 + 1 + 8),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Zero copy view over an EditionV1 account (a print of a master edition)
+pub struct EditionView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> EditionView<'a> {
+    /// key + parent + edition
+    pub const LEN: usize = 1 + 32 + 8;
+
+    /// Checks the Key of the account
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        if bytes.first() != Some(&(Key::EditionV1 as u8)) || bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Checks the owner of the account, then the Key. The data stays borrowed
+    /// until the returned `Borrowed` is dropped
+    pub fn from_account_info(account: &'a AccountInfo) -> Result<Borrowed<'a, Self>, ProgramError> {
+        Borrowed::new(account, Self::new)
+    }
+
+    /// Checks the owner of the account, then the Key
+    ///
+    /// # Safety
+    ///
+    /// Borrows the account data without going through the runtime borrow flags,
+    /// so there can't be any mutable borrow of it while the view is alive.
+    pub unsafe fn from_account_info_unchecked(
+        account: &'a AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        check_owner(account)?;
+        Self::new(account.borrow_data_unchecked())
+    }
+
+    /// The master edition this was printed from
+    pub fn parent(&self) -> &'a Pubkey {
+        // checked when creating the view
+        unsafe { &*(self.bytes[1..33].as_ptr() as *const Pubkey) }
+    }
+
+    /// The number of this edition
+    pub fn edition(&self) -> u64 {
+        // checked when creating the view
+        u64::from_le_bytes(self.bytes[33..41].try_into().unwrap())
+    }
+}
@@ -0,0 +1,74 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{authorization::AuthorizationData, discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+/// Arguments of Lock and Unlock, which take the same ones
+pub enum LockArgs<'a> {
+    V1 {
+        /// Required authorization data to validate the request.
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+}
+
+impl<'a> Serialize for LockArgs<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::V1 { authorization_data } => {
+                buffer[0] = 0;
+                1 + authorization_data.serialize_to(&mut buffer[1..])
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::V1 { authorization_data } => 1 + authorization_data.serialized_len(),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for LockArgs<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => {
+                let (authorization_data, len) = Option::deserialize_from(&bytes[1..])?;
+                Ok((Self::V1 { authorization_data }, 1 + len))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct LockInstructionData<'a> {
+    pub args: LockArgs<'a>,
+}
+
+impl<'a> Serialize for LockInstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Lock.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for LockInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Lock.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = LockArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
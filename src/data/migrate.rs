@@ -0,0 +1,134 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    data::{discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+/// What the asset is migrated to
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MigrationType {
+    CollectionV1,
+    ProgrammableV1,
+}
+
+impl Serialize for MigrationType {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = match self {
+            Self::CollectionV1 => 0,
+            Self::ProgrammableV1 => 1,
+        };
+
+        1
+    }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize<'_> for MigrationType {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((Self::CollectionV1, 1)),
+            1 => Ok((Self::ProgrammableV1, 1)),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub enum MigrateArgs {
+    V1 {
+        migration_type: MigrationType,
+        /// Rule set to set on the programmable config
+        rule_set: Option<Pubkey>,
+    },
+}
+
+impl Serialize for MigrateArgs {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::V1 {
+                migration_type,
+                rule_set,
+            } => {
+                buffer[0] = 0;
+                let mut offset = 1;
+
+                offset += migration_type.serialize_to(&mut buffer[offset..]);
+                offset += rule_set.serialize_to(&mut buffer[offset..]);
+
+                offset
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::V1 {
+                migration_type,
+                rule_set,
+            } => 1 + migration_type.serialized_len() + rule_set.serialized_len(),
+        }
+    }
+}
+
+impl Deserialize<'_> for MigrateArgs {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => {
+                let mut offset = 1;
+
+                let (migration_type, len) = MigrationType::deserialize_from(&bytes[offset..])?;
+                offset += len;
+                let (rule_set, len) = Option::deserialize_from(&bytes[offset..])?;
+                offset += len;
+
+                Ok((
+                    Self::V1 {
+                        migration_type,
+                        rule_set,
+                    },
+                    offset,
+                ))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct MigrateInstructionData {
+    pub args: MigrateArgs,
+}
+
+impl MigrateInstructionData {
+    /// discriminator + args version + migration type + rule set
+    pub const MAX_LEN: usize = 1 + 1 + 1 + 1 + 32;
+}
+
+impl Serialize for MigrateInstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Migrate.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl Deserialize<'_> for MigrateInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Migrate.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = MigrateArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
@@ -1,19 +1,17 @@
-use crate::{data::Serialize, Instructions};
+use pinocchio::program_error::ProgramError;
 
-pub struct MintInstructionData {
+use crate::{
+    data::{authorization::AuthorizationData, Deserialize, Serialize},
+    Instructions,
+};
+
+pub struct MintInstructionData<'a> {
     pub amount: u64,
     /// Required authorization data to validate the request.
-    pub authorization_data: Option<AuthorizationData>,
-}
-
-pub struct AuthorizationData {}
-impl Serialize for AuthorizationData {
-    fn serialize_to(&self, _buffer: &mut [u8]) -> usize {
-        panic!("Not implemented, did not feel like serializing a hashmap by hand");
-    }
+    pub authorization_data: Option<AuthorizationData<'a>>,
 }
 
-impl Serialize for MintInstructionData {
+impl<'a> Serialize for MintInstructionData<'a> {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         buffer[0] = Instructions::Mint.to_u8();
         let mut offset = 1;
@@ -25,6 +23,33 @@ impl Serialize for MintInstructionData {
         offset += self.amount.serialize_to(&mut buffer[offset..]);
         offset += self.authorization_data.serialize_to(&mut buffer[offset..]);
 
-        return offset;
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        2 + self.amount.serialized_len() + self.authorization_data.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for MintInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        // discriminator and the only version of the args
+        if bytes.get(..2) != Some(&[Instructions::Mint.to_u8(), 0]) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let mut offset = 2;
+
+        let (amount, len) = u64::deserialize_from(&bytes[offset..])?;
+        offset += len;
+        let (authorization_data, len) = Option::deserialize_from(&bytes[offset..])?;
+        offset += len;
+
+        Ok((
+            Self {
+                amount,
+                authorization_data,
+            },
+            offset,
+        ))
     }
 }
@@ -1,15 +1,52 @@
+use bytemuck::{try_cast_slice, Pod};
 use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
+use crate::error::PnftError;
+
+pub mod authorization;
 pub mod burn;
 pub mod create;
+pub mod create_master_edition_v3;
+pub mod create_metadata_account_v3;
+pub mod delegate;
+pub mod edition;
+pub mod lock;
+pub mod migrate;
 pub mod mint;
 pub mod nft;
+pub mod print;
+pub mod revoke;
+pub mod token_record;
 pub mod transfer;
+pub mod unlock;
+pub mod unverify;
+pub mod update;
+pub mod use_asset;
 pub mod verify;
 
 pub trait Serialize {
-    /// Serialize into a slice, starting at 0, returning how many bytes were written
+    /// Serialize into a slice, starting at 0, returning how many bytes were written.
+    /// Panics if the buffer is too small, see `try_serialize_to`
     fn serialize_to(&self, buffer: &mut [u8]) -> usize;
+
+    /// Exact number of bytes `serialize_to` will write, so the buffer can be sized up front
+    fn serialized_len(&self) -> usize;
+
+    /// Same as `serialize_to`, but checks that everything fits in the buffer first instead of panicking,
+    /// and that every string and list length fits in its u32 prefix
+    fn try_serialize_to(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        let len = self.serialized_len();
+
+        if buffer.len() < len {
+            return Err(PnftError::BufferTooSmall.into());
+        }
+        // no length prefix can be bigger than the whole value
+        if u32::try_from(len).is_err() {
+            return Err(PnftError::LengthOverflow.into());
+        }
+
+        Ok(self.serialize_to(buffer))
+    }
 }
 
 pub trait DeserializeSized {
@@ -18,10 +55,95 @@ pub trait DeserializeSized {
         Self: Sized;
 }
 
+/// Borrowing inverse of `Serialize`, to read instruction data (of another instruction in the
+/// transaction, for example). Strings and slices point into the input instead of being copied.
+pub trait Deserialize<'a>: Sized {
+    /// Deserialize from the start of the slice, returning the value and how many bytes were read
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError>;
+
+    /// Same as `deserialize_from`, but every byte has to be used, like borsh's `try_from_slice`
+    fn decode(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        let (value, len) = Self::deserialize_from(bytes)?;
+
+        if len != bytes.len() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(value)
+    }
+}
+
+/// The first `len` bytes of the slice
+#[inline(always)]
+pub(crate) fn take(bytes: &[u8], len: usize) -> Result<&[u8], ProgramError> {
+    bytes.get(..len).ok_or(ProgramError::InvalidInstructionData)
+}
+
+/// Reads a single byte enum discriminator
+#[inline(always)]
+pub(crate) fn discriminator(bytes: &[u8]) -> Result<u8, ProgramError> {
+    bytes
+        .first()
+        .copied()
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+/// Deserializes a `T` at `offset` and moves `offset` past it
+#[inline(always)]
+pub(crate) fn read<'a, T: Deserialize<'a>>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+) -> Result<T, ProgramError> {
+    let bytes = bytes
+        .get(*offset..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let (value, len) = T::deserialize_from(bytes)?;
+    *offset += len;
+    Ok(value)
+}
+
+/// Reuses the `DeserializeSized` impl of types that always take `len` bytes
+#[inline(always)]
+pub(crate) fn deserialize_sized<T: DeserializeSized>(
+    bytes: &[u8],
+    len: usize,
+) -> Result<(T, usize), ProgramError> {
+    let value =
+        T::deserialize(take(bytes, len)?).map_err(|_| ProgramError::InvalidInstructionData)?;
+    Ok((value, len))
+}
+
+/// Zero copy for slices of types with no alignment needs, the u32 length prefix is checked against the input
+pub(crate) fn deserialize_pod_slice<T: Pod>(bytes: &[u8]) -> Result<(&[T], usize), ProgramError> {
+    let (len, _) = u32::deserialize_from(bytes)?;
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| len.checked_mul(size_of::<T>()))
+        .and_then(|len| len.checked_add(4))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let items = try_cast_slice(
+        bytes
+            .get(4..end)
+            .ok_or(ProgramError::InvalidInstructionData)?,
+    )
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    Ok((items, end))
+}
+
 pub trait Skip {
     fn skip_bytes(bytes: &[u8]) -> Result<usize, ProgramError>;
 }
 
+/// Writes the u32 length prefix of a string or slice, returning how many bytes were written.
+/// `try_serialize_to` makes sure it fits, a length that doesn't would need a buffer of over 4GiB
+#[inline(always)]
+fn serialize_len_prefix(len: usize, buffer: &mut [u8]) -> usize {
+    buffer[..4].copy_from_slice(&(len as u32).to_le_bytes());
+    4
+}
+
 // faster but items must be sized
 pub fn skip_sized<T: Sized>() -> usize {
     size_of::<T>()
@@ -39,16 +161,34 @@ pub fn skip_sized_slice<T: Sized>(bytes: &[u8]) -> Result<usize, ProgramError> {
 impl Serialize for &str {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         let bytes = self.as_bytes();
-        let len = bytes.len();
-        let total_len = 4 + len;
-
-        let len_u32 = u32::try_from(len).unwrap(); // didn't feel like making this return a result
+        let total_len = 4 + bytes.len();
 
-        buffer[..4].copy_from_slice(&len_u32.to_le_bytes());
+        serialize_len_prefix(bytes.len(), buffer);
         buffer[4..total_len].copy_from_slice(bytes);
 
         total_len
     }
+
+    fn serialized_len(&self) -> usize {
+        4 + self.len()
+    }
+}
+
+impl Skip for &str {
+    fn skip_bytes(bytes: &[u8]) -> Result<usize, ProgramError> {
+        let len = u32::deserialize(bytes)?;
+        let total_len = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_add(4))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // the string has to be there
+        if bytes.len() < total_len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(total_len)
+    }
 }
 
 impl<T: Serialize> Serialize for Option<T> {
@@ -64,11 +204,40 @@ impl<T: Serialize> Serialize for Option<T> {
             }
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            None => 1,
+            Some(data) => 1 + data.serialized_len(),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for &'a str {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (string, len) = <&[u8]>::deserialize_from(bytes)?;
+        let string =
+            core::str::from_utf8(string).map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok((string, len))
+    }
+}
+
+impl<'a, T: Deserialize<'a>> Deserialize<'a> for Option<T> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((None, 1)),
+            1 => {
+                let (data, len) = T::deserialize_from(&bytes[1..])?;
+                Ok((Some(data), 1 + len))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
 }
 
 impl<T: Skip> Skip for Option<T> {
     fn skip_bytes(buffer: &[u8]) -> Result<usize, ProgramError> {
-        let disc = buffer[0];
+        let disc = buffer.first().ok_or(ProgramError::InvalidAccountData)?;
         match disc {
             0 => Ok(1),
             1 => Ok(1 + T::skip_bytes(&buffer[1..])?),
@@ -79,10 +248,7 @@ impl<T: Skip> Skip for Option<T> {
 
 impl<T: Serialize> Serialize for [T] {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
-        let len = u32::try_from(self.len()).unwrap(); // didn't feel like making this return a result
-        buffer[..4].copy_from_slice(&len.to_le_bytes());
-
-        let mut offset = 4;
+        let mut offset = serialize_len_prefix(self.len(), buffer);
 
         for item in self {
             offset += item.serialize_to(&mut buffer[offset..]);
@@ -90,14 +256,15 @@ impl<T: Serialize> Serialize for [T] {
 
         offset
     }
+
+    fn serialized_len(&self) -> usize {
+        4 + self.iter().map(Serialize::serialized_len).sum::<usize>()
+    }
 }
 
 impl<T: Serialize> Serialize for &[T] {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
-        let len = u32::try_from(self.len()).unwrap(); // didn't feel like making this return a result
-        buffer[..4].copy_from_slice(&len.to_le_bytes());
-
-        let mut offset = 4;
+        let mut offset = serialize_len_prefix(self.len(), buffer);
 
         for item in self.iter() {
             offset += item.serialize_to(&mut buffer[offset..]);
@@ -105,30 +272,62 @@ impl<T: Serialize> Serialize for &[T] {
 
         offset
     }
+
+    fn serialized_len(&self) -> usize {
+        (**self).serialized_len()
+    }
 }
 
 impl<T: Skip> Skip for &[T] {
     fn skip_bytes(bytes: &[u8]) -> Result<usize, ProgramError> {
         let len = u32::from_le_bytes(
-            bytes[0..4]
-                .try_into()
-                .map_err(|_| ProgramError::InvalidAccountData)?,
+            bytes
+                .get(0..4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ProgramError::InvalidAccountData)?,
         );
 
         let mut offset = 4;
         for _ in 0..len {
-            offset += T::skip_bytes(&bytes[offset..])?;
+            offset += T::skip_bytes(
+                bytes
+                    .get(offset..)
+                    .ok_or(ProgramError::InvalidAccountData)?,
+            )?;
         }
 
         Ok(offset)
     }
 }
 
+impl<'a> Deserialize<'a> for &'a [u8] {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_pod_slice(bytes)
+    }
+}
+
+impl<'a> Deserialize<'a> for &'a [[u8; 32]] {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        deserialize_pod_slice(bytes)
+    }
+}
+
 impl Serialize for Pubkey {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         buffer[..32].copy_from_slice(self);
         32
     }
+
+    fn serialized_len(&self) -> usize {
+        32
+    }
+}
+
+impl Deserialize<'_> for Pubkey {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        // can't fail, the length was checked
+        Ok((take(bytes, 32)?.try_into().unwrap(), 32))
+    }
 }
 
 impl Serialize for u8 {
@@ -136,6 +335,16 @@ impl Serialize for u8 {
         buffer[0] = *self;
         1
     }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize<'_> for u8 {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Ok((discriminator(bytes)?, 1))
+    }
 }
 
 impl Serialize for u16 {
@@ -143,14 +352,26 @@ impl Serialize for u16 {
         buffer[..2].copy_from_slice(&self.to_le_bytes());
         2
     }
+
+    fn serialized_len(&self) -> usize {
+        2
+    }
+}
+
+impl Deserialize<'_> for u16 {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        // can't fail, the length was checked
+        Ok((u16::from_le_bytes(take(bytes, 2)?.try_into().unwrap()), 2))
+    }
 }
 
 impl DeserializeSized for u16 {
     fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
         Ok(u16::from_le_bytes(
-            bytes[0..2]
-                .try_into()
-                .map_err(|_| ProgramError::InvalidAccountData)?,
+            bytes
+                .get(0..2)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ProgramError::InvalidAccountData)?,
         ))
     }
 }
@@ -160,14 +381,26 @@ impl Serialize for u32 {
         buffer[..4].copy_from_slice(&self.to_le_bytes());
         4
     }
+
+    fn serialized_len(&self) -> usize {
+        4
+    }
+}
+
+impl Deserialize<'_> for u32 {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        // can't fail, the length was checked
+        Ok((u32::from_le_bytes(take(bytes, 4)?.try_into().unwrap()), 4))
+    }
 }
 
 impl DeserializeSized for u32 {
     fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
         Ok(u32::from_le_bytes(
-            bytes[0..4]
-                .try_into()
-                .map_err(|_| ProgramError::InvalidAccountData)?,
+            bytes
+                .get(0..4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ProgramError::InvalidAccountData)?,
         ))
     }
 }
@@ -177,6 +410,10 @@ impl Serialize for u64 {
         buffer[..8].copy_from_slice(&self.to_le_bytes());
         8
     }
+
+    fn serialized_len(&self) -> usize {
+        8
+    }
 }
 
 impl Skip for u64 {
@@ -185,12 +422,20 @@ impl Skip for u64 {
     }
 }
 
+impl Deserialize<'_> for u64 {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        // can't fail, the length was checked
+        Ok((u64::from_le_bytes(take(bytes, 8)?.try_into().unwrap()), 8))
+    }
+}
+
 impl DeserializeSized for u64 {
     fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
         Ok(u64::from_le_bytes(
-            bytes[0..8]
-                .try_into()
-                .map_err(|_| ProgramError::InvalidAccountData)?,
+            bytes
+                .get(0..8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ProgramError::InvalidAccountData)?,
         ))
     }
 }
@@ -200,4 +445,18 @@ impl Serialize for bool {
         buffer[0] = if *self { 1 } else { 0 };
         1
     }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize<'_> for bool {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((false, 1)),
+            1 => Ok((true, 1)),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
 }
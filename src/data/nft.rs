@@ -4,9 +4,23 @@
 // these are all the constants I could find:
 
 use bytemuck::{try_cast_slice, Pod, Zeroable};
-use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
-use crate::data::{create::Collection, DeserializeSized, Serialize};
+use crate::{
+    data::{
+        create::{Collection, CollectionDetails, TokenStandard, UseMethod, Uses},
+        deserialize_pod_slice,
+        edition::{EditionView, MasterEditionView},
+        token_record::TokenRecordView,
+        Deserialize, DeserializeSized, Serialize, Skip,
+    },
+    error::PnftError,
+    MPL_TOKEN_METADATA_ID,
+};
 
 /// Maximum number of characters in a metadata name.
 pub const MAX_NAME_LENGTH: usize = 32;
@@ -29,6 +43,92 @@ pub const MAX_EDITION_MARKER_SIZE: usize = 32;
 /// Number of bits used by a edition marker.
 pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
 
+/// First byte of every account owned by the token metadata program
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Key {
+    Uninitialized = 0,
+    EditionV1 = 1,
+    MasterEditionV1 = 2,
+    ReservationListV1 = 3,
+    MetadataV1 = 4,
+    ReservationListV2 = 5,
+    MasterEditionV2 = 6,
+    EditionMarker = 7,
+    UseAuthorityRecord = 8,
+    CollectionAuthorityRecord = 9,
+    TokenOwnedEscrow = 10,
+    TokenRecord = 11,
+    MetadataDelegate = 12,
+    EditionMarkerV2 = 13,
+    HolderDelegate = 14,
+}
+
+/// Checks that the account is owned by the token metadata program
+#[inline(always)]
+pub fn check_owner(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_owned_by(&MPL_TOKEN_METADATA_ID) {
+        return Err(PnftError::InvalidOwner.into());
+    }
+
+    Ok(())
+}
+
+/// A view over account data borrowed through the runtime borrow flags, returned by the
+/// `from_account_info` constructors. The data can't be borrowed mutably until it is dropped,
+/// and `view` only lends the view for as long as it lives
+pub struct Borrowed<'a, V> {
+    view: V,
+    _data: Ref<'a, [u8]>,
+}
+
+impl<'a, V> Borrowed<'a, V> {
+    /// Borrows the data of an account owned by token metadata and builds the view with `new`
+    pub(crate) fn new(
+        account: &'a AccountInfo,
+        new: impl FnOnce(&'a [u8]) -> Result<V, ProgramError>,
+    ) -> Result<Self, ProgramError> {
+        check_owner(account)?;
+        let data = account.try_borrow_data()?;
+        // SAFETY: the data stays borrowed while `data` is alive, and the view is only handed out
+        // with the lifetime of `self`
+        let bytes = unsafe { core::slice::from_raw_parts(data.as_ptr(), data.len()) };
+
+        Ok(Self {
+            view: new(bytes)?,
+            _data: data,
+        })
+    }
+}
+
+impl Borrowed<'_, MetadataView<'_>> {
+    /// The view, for as long as the data stays borrowed
+    pub fn view(&self) -> &MetadataView<'_> {
+        &self.view
+    }
+}
+
+impl Borrowed<'_, MasterEditionView<'_>> {
+    /// The view, for as long as the data stays borrowed
+    pub fn view(&self) -> &MasterEditionView<'_> {
+        &self.view
+    }
+}
+
+impl Borrowed<'_, EditionView<'_>> {
+    /// The view, for as long as the data stays borrowed
+    pub fn view(&self) -> &EditionView<'_> {
+        &self.view
+    }
+}
+
+impl Borrowed<'_, TokenRecordView<'_>> {
+    /// The view, for as long as the data stays borrowed
+    pub fn view(&self) -> &TokenRecordView<'_> {
+        &self.view
+    }
+}
+
 #[derive(Pod, Zeroable, Copy, Clone)]
 #[repr(C)]
 pub struct Creator {
@@ -37,6 +137,19 @@ pub struct Creator {
     pub share: u8,
 }
 
+impl<'a> Deserialize<'a> for &'a [Creator] {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let (creators, len): (&[Creator], usize) = deserialize_pod_slice(bytes)?;
+
+        // verified is a bool, borsh doesn't accept anything else
+        if creators.iter().any(|creator| creator.verified > 1) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok((creators, len))
+    }
+}
+
 impl Serialize for Creator {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         let mut offset = 0;
@@ -45,6 +158,10 @@ impl Serialize for Creator {
         offset += self.share.serialize_to(&mut buffer[offset..]);
         offset
     }
+
+    fn serialized_len(&self) -> usize {
+        MAX_CREATOR_LEN
+    }
 }
 
 pub struct MetadataInfo<'a> {
@@ -55,84 +172,372 @@ pub struct MetadataInfo<'a> {
 }
 
 // For now, all I need is to be able to deserialize royalties and nothing else
-// Since I know the exact length of all fields before the royalties, I can very conveniently just skip all of the bytes
-// Just like in the mpl core lib, the metaplex gods have bestowed upon me a struct with no alignment needs, so I can just
-// zero copy the whole thing
+// All the heavy lifting is done by MetadataView, this just grabs the fields I care about
 pub fn read_metadata_info<'a>(bytes: &'a [u8]) -> Result<MetadataInfo<'a>, ProgramError> {
-    // I can skip everything but I'm just going to check that the Key is correct
-    if bytes[0] != 4 {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let view = MetadataView::new(bytes)?;
+
+    Ok(MetadataInfo {
+        mint: view.mint()?,
+        basis_points: view.seller_fee_basis_points()?,
+        creators: view.creators()?.unwrap_or(&[]),
+        collection: view.collection()?,
+    })
+}
 
-    // the mint starts at byte #33
-    let mint: &[u8; 32] = {
-        // bounds check so we can use unsafe
-        let mint_slice = bytes
-            .get(33..33 + 32)
+pub enum ProgrammableConfig<'a> {
+    V1 { rule_set: Option<&'a Pubkey> },
+}
+
+/// Zero copy view over the Uses of a metadata, see `MetadataView::uses_view`
+pub struct UsesView<'a> {
+    bytes: &'a [u8; Uses::LEN],
+}
+
+impl<'a> UsesView<'a> {
+    /// Checks that there are enough bytes, anything after the Uses is ignored
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        let bytes = bytes
+            .get(..Uses::LEN)
+            .and_then(|bytes| bytes.try_into().ok())
             .ok_or(ProgramError::InvalidAccountData)?;
-        
-        unsafe { &*(mint_slice.as_ptr() as *const [u8; 32]) }
-    };
-    
-    // see the diagram https://github.com/metaplex-foundation/mpl-token-metadata/blob/main/programs/token-metadata/program/ProgrammableNFTGuide.md
-    // it already has sizes. note that name has 4 bytes for the length + 200 for the actual string. this is absolutely completely retarded, btw, they are wasting space just because. who the fuck designed this?
-    // counting from 0, we can skip 319 bytes
-    const BYTES_TO_SKIP: usize = 319;
-    let mut offset = BYTES_TO_SKIP;
-
-    // next two bytes are the basis points
-    let basis_points = u16::deserialize(&bytes[offset..])?;
-    offset += size_of::<u16>();
-
-    // then an Option<Vec<Creator>>
-    // read the option discriminator
-    let option_disc = bytes[offset];
-    offset += 1;
-
-    let creators = match option_disc {
-        0 => &[],
-        _ => {
-            // read the len
-            let num_creators = usize::try_from(u32::deserialize(&bytes[offset..])?)
-                .map_err(|_| ProgramError::ArithmeticOverflow)?;
-            offset += size_of::<u32>();
-            let creators_start = offset;
-            let creators_end = creators_start + (num_creators * size_of::<Creator>());
-
-            // read the creators
-            let creators: &[Creator] = try_cast_slice(&bytes[creators_start..creators_end])
+
+        Ok(Self { bytes })
+    }
+
+    pub fn use_method(&self) -> Result<UseMethod, ProgramError> {
+        UseMethod::deserialize(self.bytes)
+    }
+
+    /// How many uses are left
+    pub fn remaining(&self) -> u64 {
+        u64::from_le_bytes(self.bytes[1..9].try_into().unwrap())
+    }
+
+    pub fn total(&self) -> u64 {
+        u64::from_le_bytes(self.bytes[9..17].try_into().unwrap())
+    }
+}
+
+// see the diagram https://github.com/metaplex-foundation/mpl-token-metadata/blob/main/programs/token-metadata/program/ProgrammableNFTGuide.md
+// it already has sizes. note that name has 4 bytes for the length + 200 for the actual string. this is absolutely completely retarded, btw, they are wasting space just because. who the fuck designed this?
+// turns out this is called "puffing", and not every account is puffed: older ones and the ones made with
+// CreateMetadataAccountV3 can have strings of any length (up to the max), so these are only valid if the lengths check out
+const NAME_OFFSET: usize = 1 + 32 + 32;
+const PUFFED_SYMBOL_OFFSET: usize = NAME_OFFSET + 4 + MAX_NAME_LENGTH;
+const PUFFED_URI_OFFSET: usize = PUFFED_SYMBOL_OFFSET + 4 + MAX_SYMBOL_LENGTH;
+// counting from 0, we can skip 319 bytes
+const PUFFED_SELLER_FEE_BASIS_POINTS_OFFSET: usize = PUFFED_URI_OFFSET + 4 + MAX_URI_LENGTH;
+
+/// Zero copy view over a Metadata account.
+///
+/// Only the position of the strings is worked out up front, every other getter finds its own field when called.
+/// Fields after the creators depend on what came before, so reading them walks
+/// over the previous ones; if you need many fields, grab them once and keep them around.
+///
+/// Strings are returned as raw bytes. Puffed accounts will have them padded with `\0` up to their max length.
+pub struct MetadataView<'a> {
+    bytes: &'a [u8],
+    symbol_offset: usize,
+    uri_offset: usize,
+    seller_fee_basis_points_offset: usize,
+}
+
+impl<'a> MetadataView<'a> {
+    /// Checks the Key of the account and finds where the strings end
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        if bytes.first() != Some(&(Key::MetadataV1 as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (symbol_offset, uri_offset, seller_fee_basis_points_offset) = if is_puffed(bytes) {
+            (
+                PUFFED_SYMBOL_OFFSET,
+                PUFFED_URI_OFFSET,
+                PUFFED_SELLER_FEE_BASIS_POINTS_OFFSET,
+            )
+        } else {
+            // walk over the strings using their length prefixes
+            let symbol_offset = NAME_OFFSET + <&str>::skip_bytes(field(bytes, NAME_OFFSET)?)?;
+            let uri_offset = symbol_offset + <&str>::skip_bytes(field(bytes, symbol_offset)?)?;
+            let seller_fee_basis_points_offset =
+                uri_offset + <&str>::skip_bytes(field(bytes, uri_offset)?)?;
+
+            (symbol_offset, uri_offset, seller_fee_basis_points_offset)
+        };
+
+        Ok(Self {
+            bytes,
+            symbol_offset,
+            uri_offset,
+            seller_fee_basis_points_offset,
+        })
+    }
+
+    /// Checks the owner of the account, then the Key. The data stays borrowed
+    /// until the returned `Borrowed` is dropped
+    pub fn from_account_info(account: &'a AccountInfo) -> Result<Borrowed<'a, Self>, ProgramError> {
+        Borrowed::new(account, Self::new)
+    }
+
+    /// Whether the name, symbol and uri are padded to their max length
+    pub fn is_puffed(&self) -> bool {
+        is_puffed(self.bytes)
+    }
+
+    pub fn update_authority(&self) -> Result<&'a Pubkey, ProgramError> {
+        read_pubkey(self.bytes, 1)
+    }
+
+    pub fn mint(&self) -> Result<&'a Pubkey, ProgramError> {
+        read_pubkey(self.bytes, 33)
+    }
+
+    pub fn name(&self) -> Result<&'a [u8], ProgramError> {
+        read_str(self.bytes, NAME_OFFSET)
+    }
+
+    pub fn symbol(&self) -> Result<&'a [u8], ProgramError> {
+        read_str(self.bytes, self.symbol_offset)
+    }
+
+    pub fn uri(&self) -> Result<&'a [u8], ProgramError> {
+        read_str(self.bytes, self.uri_offset)
+    }
+
+    pub fn seller_fee_basis_points(&self) -> Result<u16, ProgramError> {
+        u16::deserialize(self.field(self.seller_fee_basis_points_offset)?)
+    }
+
+    pub fn creators(&self) -> Result<Option<&'a [Creator]>, ProgramError> {
+        let offset = self.creators_offset();
+
+        // Option<Vec<Creator>>
+        match self.field(offset)?[0] {
+            0 => Ok(None),
+            1 => {
+                // the creators also have no alignment needs, so just zero copy all of them
+                let num_creators = usize::try_from(u32::deserialize(self.field(offset + 1)?)?)
+                    .map_err(|_| ProgramError::ArithmeticOverflow)?;
+                let creators_start = offset + 1 + size_of::<u32>();
+                let creators_end = num_creators
+                    .checked_mul(size_of::<Creator>())
+                    .and_then(|len| len.checked_add(creators_start))
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+
+                let creators: &[Creator] = try_cast_slice(
+                    self.bytes
+                        .get(creators_start..creators_end)
+                        .ok_or(ProgramError::InvalidAccountData)?,
+                )
                 .map_err(|_| ProgramError::InvalidAccountData)?;
-            offset = creators_end;
 
-            creators
+                Ok(Some(creators))
+            }
+            _ => Err(ProgramError::InvalidAccountData),
         }
-    };
+    }
+
+    pub fn primary_sale_happened(&self) -> Result<bool, ProgramError> {
+        Ok(self.field(self.primary_sale_happened_offset()?)?[0] != 0)
+    }
+
+    pub fn is_mutable(&self) -> Result<bool, ProgramError> {
+        Ok(self.field(self.primary_sale_happened_offset()? + 1)?[0] != 0)
+    }
+
+    pub fn edition_nonce(&self) -> Result<Option<u8>, ProgramError> {
+        match self.option(self.edition_nonce_offset()?)? {
+            Some(nonce) => Ok(Some(self.field(nonce)?[0])),
+            None => Ok(None),
+        }
+    }
+
+    pub fn token_standard(&self) -> Result<Option<TokenStandard>, ProgramError> {
+        match self.option(self.token_standard_offset()?)? {
+            Some(token_standard) => Ok(Some(TokenStandard::deserialize(
+                self.field(token_standard)?,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn collection(&self) -> Result<Option<&'a Collection>, ProgramError> {
+        match self.option(self.collection_offset()?)? {
+            // the collection also has no alignment needs, so just zero copy the entire thing
+            Some(collection) => Ok(Some(
+                bytemuck::try_from_bytes(
+                    self.bytes
+                        .get(collection..collection + size_of::<Collection>())
+                        .ok_or(ProgramError::InvalidAccountData)?,
+                )
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub fn uses(&self) -> Result<Option<Uses>, ProgramError> {
+        match self.option(self.uses_offset()?)? {
+            Some(uses) => Ok(Some(Uses::deserialize(self.field(uses)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Same as `uses` without copying, to only read what is needed
+    pub fn uses_view(&self) -> Result<Option<UsesView<'a>>, ProgramError> {
+        match self.option(self.uses_offset()?)? {
+            Some(uses) => Ok(Some(UsesView::new(self.field(uses)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn collection_details(&self) -> Result<Option<CollectionDetails>, ProgramError> {
+        match self.option(self.collection_details_offset()?)? {
+            Some(details) => Ok(Some(CollectionDetails::deserialize(self.field(details)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn programmable_config(&self) -> Result<Option<ProgrammableConfig<'a>>, ProgramError> {
+        let offset = match self.option(self.programmable_config_offset()?)? {
+            Some(offset) => offset,
+            None => return Ok(None),
+        };
+
+        // ProgrammableConfig only has the V1 variant
+        if self.field(offset)?[0] != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let rule_set = match self.option(offset + 1)? {
+            Some(rule_set) => Some(read_pubkey(self.bytes, rule_set)?),
+            None => None,
+        };
+
+        Ok(Some(ProgrammableConfig::V1 { rule_set }))
+    }
 
-    // skip over some more stuff
-    let _primary_sale_happened = bytes[offset];
-    offset += 1;
-    let _is_mutable = bytes[offset];
-    offset += 1;
-
-    // edition nonce and token standard
-    offset += 4;
-
-    // collection is an Option<Collection>
-    // the collection also has no alignment needs, so just zero copy the entire thing
-    let collection = match bytes[offset] {
-        0 => None,
-        _ => {
-            offset += 1;
-            Some(bytemuck::from_bytes(
-                &bytes[offset..offset + size_of::<Collection>()],
-            ))
+    #[inline(always)]
+    fn field(&self, offset: usize) -> Result<&'a [u8], ProgramError> {
+        field(self.bytes, offset)
+    }
+
+    #[inline(always)]
+    fn creators_offset(&self) -> usize {
+        self.seller_fee_basis_points_offset + size_of::<u16>()
+    }
+
+    /// Reads an Option discriminator, returning where the inner value starts.
+    /// Fields at the end were added over time, older accounts can just not have them,
+    /// so running out of bytes is the same as None
+    #[inline(always)]
+    fn option(&self, offset: usize) -> Result<Option<usize>, ProgramError> {
+        match self.bytes.get(offset) {
+            None | Some(0) => Ok(None),
+            Some(1) => Ok(Some(offset + 1)),
+            _ => Err(ProgramError::InvalidAccountData),
         }
+    }
+
+    /// Same as `option` but only returns how many bytes the whole Option takes up
+    #[inline(always)]
+    fn skip_option(&self, offset: usize, inner_size: usize) -> Result<usize, ProgramError> {
+        match self.option(offset)? {
+            Some(_) => Ok(1 + inner_size),
+            None => Ok(1),
+        }
+    }
+
+    fn primary_sale_happened_offset(&self) -> Result<usize, ProgramError> {
+        let creators_len = match self.creators()? {
+            Some(creators) => 1 + size_of::<u32>() + size_of_val(creators),
+            None => 1,
+        };
+
+        Ok(self.creators_offset() + creators_len)
+    }
+
+    fn edition_nonce_offset(&self) -> Result<usize, ProgramError> {
+        // primary_sale_happened and is_mutable
+        Ok(self.primary_sale_happened_offset()? + 2)
+    }
+
+    fn token_standard_offset(&self) -> Result<usize, ProgramError> {
+        let offset = self.edition_nonce_offset()?;
+        Ok(offset + self.skip_option(offset, size_of::<u8>())?)
+    }
+
+    fn collection_offset(&self) -> Result<usize, ProgramError> {
+        let offset = self.token_standard_offset()?;
+        Ok(offset + self.skip_option(offset, 1)?)
+    }
+
+    fn uses_offset(&self) -> Result<usize, ProgramError> {
+        let offset = self.collection_offset()?;
+        Ok(offset + self.skip_option(offset, size_of::<Collection>())?)
+    }
+
+    fn collection_details_offset(&self) -> Result<usize, ProgramError> {
+        let offset = self.uses_offset()?;
+        // use method + remaining + total
+        Ok(offset + self.skip_option(offset, 1 + 8 + 8)?)
+    }
+
+    fn programmable_config_offset(&self) -> Result<usize, ProgramError> {
+        let offset = self.collection_details_offset()?;
+        // both versions are 1 byte + 8 bytes
+        Ok(offset + self.skip_option(offset, 1 + 8)?)
+    }
+}
+
+/// Slice starting at `offset`, with at least one byte in it
+#[inline(always)]
+fn field(bytes: &[u8], offset: usize) -> Result<&[u8], ProgramError> {
+    match bytes.get(offset..) {
+        Some(bytes) if !bytes.is_empty() => Ok(bytes),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Checking this reads the same length prefixes walking the strings would,
+/// but then all the offsets are constants
+fn is_puffed(bytes: &[u8]) -> bool {
+    let len_is = |offset: usize, max: usize| {
+        bytes
+            .get(offset..)
+            .and_then(|bytes| u32::deserialize(bytes).ok())
+            .is_some_and(|len| len as usize == max)
     };
 
-    Ok(MetadataInfo {
-        mint,
-        basis_points,
-        creators,
-        collection,
-    })
+    len_is(NAME_OFFSET, MAX_NAME_LENGTH)
+        && len_is(PUFFED_SYMBOL_OFFSET, MAX_SYMBOL_LENGTH)
+        && len_is(PUFFED_URI_OFFSET, MAX_URI_LENGTH)
+}
+
+pub(crate) fn read_pubkey(bytes: &[u8], offset: usize) -> Result<&Pubkey, ProgramError> {
+    // bounds check so we can use unsafe
+    let slice = bytes
+        .get(offset..offset + 32)
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    Ok(unsafe { &*(slice.as_ptr() as *const [u8; 32]) })
+}
+
+fn read_str(bytes: &[u8], offset: usize) -> Result<&[u8], ProgramError> {
+    let len = usize::try_from(u32::deserialize(
+        bytes
+            .get(offset..)
+            .ok_or(ProgramError::InvalidAccountData)?,
+    )?)
+    .map_err(|_| ProgramError::ArithmeticOverflow)?;
+    let start = offset + size_of::<u32>();
+
+    bytes
+        .get(
+            start
+                ..start
+                    .checked_add(len)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+        )
+        .ok_or(ProgramError::InvalidAccountData)
 }
@@ -0,0 +1,86 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+/// Both versions have the same data, V2 lets a holder delegate print with the
+/// `holder_delegate_record` and `delegate` accounts of `Print`
+pub enum PrintArgs {
+    V1 {
+        /// Number of the edition to print, it has to be free in the edition marker
+        edition: u64,
+    },
+    V2 {
+        /// Number of the edition to print, it has to be free in the edition marker
+        edition: u64,
+    },
+}
+
+impl Serialize for PrintArgs {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let (version, edition) = match self {
+            Self::V1 { edition } => (0, edition),
+            Self::V2 { edition } => (1, edition),
+        };
+        buffer[0] = version;
+
+        1 + edition.serialize_to(&mut buffer[1..])
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + 8
+    }
+}
+
+impl Deserialize<'_> for PrintArgs {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        let version = discriminator(bytes)?;
+        let (edition, len) = u64::deserialize_from(&bytes[1..])?;
+
+        let args = match version {
+            0 => Self::V1 { edition },
+            1 => Self::V2 { edition },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok((args, 1 + len))
+    }
+}
+
+pub struct PrintInstructionData {
+    pub args: PrintArgs,
+}
+
+impl PrintInstructionData {
+    /// discriminator + args version + edition
+    pub const MAX_LEN: usize = 1 + 1 + 8;
+}
+
+impl Serialize for PrintInstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Print.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::MAX_LEN
+    }
+}
+
+impl Deserialize<'_> for PrintInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Print.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = PrintArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
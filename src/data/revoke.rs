@@ -0,0 +1,112 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+pub enum RevokeArgs {
+    CollectionV1,
+    SaleV1,
+    TransferV1,
+    DataV1,
+    UtilityV1,
+    StakingV1,
+    StandardV1,
+    LockedTransferV1,
+    ProgrammableConfigV1,
+    MigrationV1,
+    AuthorityItemV1,
+    DataItemV1,
+    CollectionItemV1,
+    ProgrammableConfigItemV1,
+    PrintDelegateV1,
+}
+
+impl Serialize for RevokeArgs {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = match self {
+            Self::CollectionV1 => 0,
+            Self::SaleV1 => 1,
+            Self::TransferV1 => 2,
+            Self::DataV1 => 3,
+            Self::UtilityV1 => 4,
+            Self::StakingV1 => 5,
+            Self::StandardV1 => 6,
+            Self::LockedTransferV1 => 7,
+            Self::ProgrammableConfigV1 => 8,
+            Self::MigrationV1 => 9,
+            Self::AuthorityItemV1 => 10,
+            Self::DataItemV1 => 11,
+            Self::CollectionItemV1 => 12,
+            Self::ProgrammableConfigItemV1 => 13,
+            Self::PrintDelegateV1 => 14,
+        };
+        1
+    }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize<'_> for RevokeArgs {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        let args = match discriminator(bytes)? {
+            0 => Self::CollectionV1,
+            1 => Self::SaleV1,
+            2 => Self::TransferV1,
+            3 => Self::DataV1,
+            4 => Self::UtilityV1,
+            5 => Self::StakingV1,
+            6 => Self::StandardV1,
+            7 => Self::LockedTransferV1,
+            8 => Self::ProgrammableConfigV1,
+            9 => Self::MigrationV1,
+            10 => Self::AuthorityItemV1,
+            11 => Self::DataItemV1,
+            12 => Self::CollectionItemV1,
+            13 => Self::ProgrammableConfigItemV1,
+            14 => Self::PrintDelegateV1,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok((args, 1))
+    }
+}
+
+pub struct RevokeInstructionData {
+    pub args: RevokeArgs,
+}
+
+impl RevokeInstructionData {
+    /// discriminator + args
+    pub const MAX_LEN: usize = 2;
+}
+
+impl Serialize for RevokeInstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Revoke.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::MAX_LEN
+    }
+}
+
+impl Deserialize<'_> for RevokeInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Revoke.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = RevokeArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
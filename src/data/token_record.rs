@@ -0,0 +1,162 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::data::{
+    nft::{check_owner, read_pubkey, Borrowed, Key},
+    DeserializeSized,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TokenState {
+    /// Token account is unlocked; operations are allowed on this account.
+    Unlocked,
+    /// Token account has been locked; no operations are allowed on this account.
+    Locked,
+    /// Token account has a `Sale` delegate set; operations are restricted.
+    Listed,
+}
+
+impl DeserializeSized for TokenState {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        match bytes.first() {
+            Some(0) => Ok(Self::Unlocked),
+            Some(1) => Ok(Self::Locked),
+            Some(2) => Ok(Self::Listed),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TokenDelegateRole {
+    Sale,
+    Transfer,
+    Utility,
+    Staking,
+    Standard,
+    LockedTransfer,
+    Migration,
+}
+
+impl DeserializeSized for TokenDelegateRole {
+    fn deserialize(bytes: &[u8]) -> Result<Self, ProgramError> {
+        match bytes.first() {
+            Some(0) => Ok(Self::Sale),
+            Some(1) => Ok(Self::Transfer),
+            Some(2) => Ok(Self::Utility),
+            Some(3) => Ok(Self::Staking),
+            Some(4) => Ok(Self::Standard),
+            Some(5) => Ok(Self::LockedTransfer),
+            Some(6) => Ok(Self::Migration),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Zero copy view over a TokenRecord account (the pNFT state of a token account)
+///
+/// Like the metadata view, fields are only read when asked for. The options make the offsets
+/// depend on each other, so later fields walk over the ones before them.
+pub struct TokenRecordView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> TokenRecordView<'a> {
+    /// Size of the account, even with every option set
+    pub const LEN: usize = 80;
+
+    /// Checks the Key of the account
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ProgramError> {
+        // key + bump + state + rule_set_revision option byte
+        if bytes.len() < 4 || bytes[0] != Key::TokenRecord as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Checks the owner of the account, then the Key. The data stays borrowed
+    /// until the returned `Borrowed` is dropped
+    pub fn from_account_info(account: &'a AccountInfo) -> Result<Borrowed<'a, Self>, ProgramError> {
+        Borrowed::new(account, Self::new)
+    }
+
+    /// Checks the owner of the account, then the Key
+    ///
+    /// # Safety
+    ///
+    /// Borrows the account data without going through the runtime borrow flags,
+    /// so there can't be any mutable borrow of it while the view is alive.
+    pub unsafe fn from_account_info_unchecked(
+        account: &'a AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        check_owner(account)?;
+        Self::new(account.borrow_data_unchecked())
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.bytes[1]
+    }
+
+    pub fn state(&self) -> Result<TokenState, ProgramError> {
+        TokenState::deserialize(&self.bytes[2..])
+    }
+
+    pub fn rule_set_revision(&self) -> Result<Option<u64>, ProgramError> {
+        match self.option(3)? {
+            Some(revision) => Ok(Some(u64::deserialize(&self.bytes[revision..])?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn delegate(&self) -> Result<Option<&'a Pubkey>, ProgramError> {
+        match self.option(self.delegate_offset()?)? {
+            Some(delegate) => Ok(Some(read_pubkey(self.bytes, delegate)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn delegate_role(&self) -> Result<Option<TokenDelegateRole>, ProgramError> {
+        match self.option(self.delegate_role_offset()?)? {
+            Some(role) => Ok(Some(TokenDelegateRole::deserialize(&self.bytes[role..])?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn locked_transfer(&self) -> Result<Option<&'a Pubkey>, ProgramError> {
+        match self.option(self.locked_transfer_offset()?)? {
+            Some(locked_transfer) => Ok(Some(read_pubkey(self.bytes, locked_transfer)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads an Option discriminator, returning where the inner value starts
+    #[inline(always)]
+    fn option(&self, offset: usize) -> Result<Option<usize>, ProgramError> {
+        match self.bytes.get(offset) {
+            Some(0) => Ok(None),
+            Some(1) if offset + 1 < self.bytes.len() => Ok(Some(offset + 1)),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Same as `option` but returns where the next field starts
+    #[inline(always)]
+    fn skip_option(&self, offset: usize, inner_size: usize) -> Result<usize, ProgramError> {
+        match self.option(offset)? {
+            Some(inner) => Ok(inner + inner_size),
+            None => Ok(offset + 1),
+        }
+    }
+
+    fn delegate_offset(&self) -> Result<usize, ProgramError> {
+        self.skip_option(3, size_of::<u64>())
+    }
+
+    fn delegate_role_offset(&self) -> Result<usize, ProgramError> {
+        self.skip_option(self.delegate_offset()?, size_of::<Pubkey>())
+    }
+
+    fn locked_transfer_offset(&self) -> Result<usize, ProgramError> {
+        self.skip_option(self.delegate_role_offset()?, 1)
+    }
+}
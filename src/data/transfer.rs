@@ -1,30 +1,97 @@
-use crate::{data::Serialize, Instructions};
+use pinocchio::program_error::ProgramError;
 
-pub struct TransferInstructionData {
-    pub amount: u64,
-    /// Required authorization data to validate the request.
-    pub authorization_data: Option<AuthorizationData>,
+use crate::{
+    data::{authorization::AuthorizationData, discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+pub enum TransferArgs<'a> {
+    V1 {
+        amount: u64,
+        /// Required authorization data to validate the request.
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
 }
 
-pub struct AuthorizationData {}
-impl Serialize for AuthorizationData {
-    fn serialize_to(&self, _buffer: &mut [u8]) -> usize {
-        panic!("Not implemented, did not feel like serializing a hashmap by hand");
+impl<'a> Serialize for TransferArgs<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::V1 {
+                amount,
+                authorization_data,
+            } => {
+                buffer[0] = 0;
+                let mut offset = 1;
+
+                offset += amount.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+
+                offset
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::V1 {
+                amount,
+                authorization_data,
+            } => 1 + amount.serialized_len() + authorization_data.serialized_len(),
+        }
     }
 }
 
-impl Serialize for TransferInstructionData {
+impl<'a> Deserialize<'a> for TransferArgs<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => {
+                let mut offset = 1;
+
+                let (amount, len) = u64::deserialize_from(&bytes[offset..])?;
+                offset += len;
+                let (authorization_data, len) = Option::deserialize_from(&bytes[offset..])?;
+                offset += len;
+
+                Ok((
+                    Self::V1 {
+                        amount,
+                        authorization_data,
+                    },
+                    offset,
+                ))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct TransferInstructionData<'a> {
+    pub args: TransferArgs<'a>,
+}
+
+impl<'a> Serialize for TransferInstructionData<'a> {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
         buffer[0] = Instructions::Transfer.to_u8();
         let mut offset = 1;
 
-        // instruction data is actually and enum so write the first byte
-        buffer[offset] = 0;
-        offset += 1;
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for TransferInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Transfer.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
-        offset += self.amount.serialize_to(&mut buffer[offset..]);
-        offset += self.authorization_data.serialize_to(&mut buffer[offset..]);
+        let (args, len) = TransferArgs::deserialize_from(&bytes[1..])?;
 
-        return offset;
+        Ok((Self { args }, 1 + len))
     }
 }
@@ -0,0 +1,40 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{lock::LockArgs, Deserialize, Serialize},
+    Instructions,
+};
+
+/// Same arguments as Lock
+pub type UnlockArgs<'a> = LockArgs<'a>;
+
+pub struct UnlockInstructionData<'a> {
+    pub args: UnlockArgs<'a>,
+}
+
+impl<'a> Serialize for UnlockInstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Unlock.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for UnlockInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Unlock.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = UnlockArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
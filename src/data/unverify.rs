@@ -0,0 +1,42 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{verify::VerificationArgs, Deserialize, Serialize},
+    Instructions,
+};
+
+pub struct UnverifyInstructionData {
+    pub args: VerificationArgs,
+}
+
+impl UnverifyInstructionData {
+    /// discriminator + args
+    pub const MAX_LEN: usize = 2;
+}
+
+impl Serialize for UnverifyInstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Unverify.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::MAX_LEN
+    }
+}
+
+impl Deserialize<'_> for UnverifyInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Unverify.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = VerificationArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
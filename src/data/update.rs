@@ -0,0 +1,494 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    data::{
+        authorization::AuthorizationData,
+        create::{Collection, CollectionDetails, TokenStandard, Uses},
+        discriminator,
+        nft::{
+            Creator, MAX_CREATOR_LEN, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH,
+            MAX_URI_LENGTH,
+        },
+        read, Deserialize, Serialize,
+    },
+    Instructions,
+};
+
+/// Subset of the metadata that can be changed by an update.
+/// `AssetData` and `DataV2` start with the same fields and reuse its encoding
+#[derive(Clone, Copy)]
+pub struct Data<'a> {
+    /// The name of the asset.
+    pub name: &'a [u8],
+    /// The symbol for the asset.
+    pub symbol: &'a [u8],
+    /// URI pointing to JSON representing the asset.
+    pub uri: &'a [u8],
+    /// Royalty basis points that goes to creators in secondary sales (0-10000).
+    pub seller_fee_basis_points: u16,
+    /// Array of creators.
+    pub creators: Option<&'a [Creator]>,
+}
+
+impl Data<'_> {
+    /// Every string at its max length and max creators
+    pub const MAX_LEN: usize = (4 + MAX_NAME_LENGTH)
+        + (4 + MAX_SYMBOL_LENGTH)
+        + (4 + MAX_URI_LENGTH)
+        + 2 // seller_fee_basis_points
+        + (1 + 4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN);
+}
+
+impl<'a> Serialize for Data<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let mut offset = 0;
+
+        offset += self.name.serialize_to(&mut buffer[offset..]);
+        offset += self.symbol.serialize_to(&mut buffer[offset..]);
+        offset += self.uri.serialize_to(&mut buffer[offset..]);
+        offset += self
+            .seller_fee_basis_points
+            .serialize_to(&mut buffer[offset..]);
+        offset += self.creators.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.name.serialized_len()
+            + self.symbol.serialized_len()
+            + self.uri.serialized_len()
+            + self.seller_fee_basis_points.serialized_len()
+            + self.creators.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for Data<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let mut offset = 0;
+
+        // strings have to be valid utf8 for borsh, even if they are kept as bytes here
+        let data = Self {
+            name: read::<&str>(bytes, &mut offset)?.as_bytes(),
+            symbol: read::<&str>(bytes, &mut offset)?.as_bytes(),
+            uri: read::<&str>(bytes, &mut offset)?.as_bytes(),
+            seller_fee_basis_points: read(bytes, &mut offset)?,
+            creators: read(bytes, &mut offset)?,
+        };
+
+        Ok((data, offset))
+    }
+}
+
+/// Tells the program what to do with an optional field:
+/// leave it as is, clear it, or set it to a new value
+pub enum Toggle<T> {
+    None,
+    Clear,
+    Set(T),
+}
+
+impl<T: Serialize> Serialize for Toggle<T> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::None => {
+                buffer[0] = 0;
+                1
+            }
+            Self::Clear => {
+                buffer[0] = 1;
+                1
+            }
+            Self::Set(value) => {
+                buffer[0] = 2;
+                1 + value.serialize_to(&mut buffer[1..])
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::None | Self::Clear => 1,
+            Self::Set(value) => 1 + value.serialized_len(),
+        }
+    }
+}
+
+impl<'a, T: Deserialize<'a>> Deserialize<'a> for Toggle<T> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((Self::None, 1)),
+            1 => Ok((Self::Clear, 1)),
+            2 => {
+                let (value, len) = T::deserialize_from(&bytes[1..])?;
+                Ok((Self::Set(value), 1 + len))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub type CollectionToggle = Toggle<Collection>;
+pub type CollectionDetailsToggle = Toggle<CollectionDetails>;
+pub type UsesToggle = Toggle<Uses>;
+pub type RuleSetToggle = Toggle<Pubkey>;
+
+pub enum UpdateArgs<'a> {
+    V1 {
+        new_update_authority: Option<Pubkey>,
+        data: Option<Data<'a>>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        collection: CollectionToggle,
+        collection_details: CollectionDetailsToggle,
+        uses: UsesToggle,
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsUpdateAuthorityV2 {
+        new_update_authority: Option<Pubkey>,
+        data: Option<Data<'a>>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        collection: CollectionToggle,
+        collection_details: CollectionDetailsToggle,
+        uses: UsesToggle,
+        rule_set: RuleSetToggle,
+        token_standard: Option<TokenStandard>,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsAuthorityItemDelegateV2 {
+        new_update_authority: Option<Pubkey>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        token_standard: Option<TokenStandard>,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsCollectionDelegateV2 {
+        collection: CollectionToggle,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsDataDelegateV2 {
+        data: Option<Data<'a>>,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsProgrammableConfigDelegateV2 {
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsDataItemDelegateV2 {
+        data: Option<Data<'a>>,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsCollectionItemDelegateV2 {
+        collection: CollectionToggle,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+    AsProgrammableConfigItemDelegateV2 {
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+}
+
+impl<'a> Serialize for UpdateArgs<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        let mut offset = 1;
+
+        match self {
+            Self::V1 {
+                new_update_authority,
+                data,
+                primary_sale_happened,
+                is_mutable,
+                collection,
+                collection_details,
+                uses,
+                rule_set,
+                authorization_data,
+            } => {
+                buffer[0] = 0;
+                offset += new_update_authority.serialize_to(&mut buffer[offset..]);
+                offset += data.serialize_to(&mut buffer[offset..]);
+                offset += primary_sale_happened.serialize_to(&mut buffer[offset..]);
+                offset += is_mutable.serialize_to(&mut buffer[offset..]);
+                offset += collection.serialize_to(&mut buffer[offset..]);
+                offset += collection_details.serialize_to(&mut buffer[offset..]);
+                offset += uses.serialize_to(&mut buffer[offset..]);
+                offset += rule_set.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsUpdateAuthorityV2 {
+                new_update_authority,
+                data,
+                primary_sale_happened,
+                is_mutable,
+                collection,
+                collection_details,
+                uses,
+                rule_set,
+                token_standard,
+                authorization_data,
+            } => {
+                buffer[0] = 1;
+                offset += new_update_authority.serialize_to(&mut buffer[offset..]);
+                offset += data.serialize_to(&mut buffer[offset..]);
+                offset += primary_sale_happened.serialize_to(&mut buffer[offset..]);
+                offset += is_mutable.serialize_to(&mut buffer[offset..]);
+                offset += collection.serialize_to(&mut buffer[offset..]);
+                offset += collection_details.serialize_to(&mut buffer[offset..]);
+                offset += uses.serialize_to(&mut buffer[offset..]);
+                offset += rule_set.serialize_to(&mut buffer[offset..]);
+                offset += token_standard.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsAuthorityItemDelegateV2 {
+                new_update_authority,
+                primary_sale_happened,
+                is_mutable,
+                token_standard,
+                authorization_data,
+            } => {
+                buffer[0] = 2;
+                offset += new_update_authority.serialize_to(&mut buffer[offset..]);
+                offset += primary_sale_happened.serialize_to(&mut buffer[offset..]);
+                offset += is_mutable.serialize_to(&mut buffer[offset..]);
+                offset += token_standard.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsCollectionDelegateV2 {
+                collection,
+                authorization_data,
+            } => {
+                buffer[0] = 3;
+                offset += collection.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsDataDelegateV2 {
+                data,
+                authorization_data,
+            } => {
+                buffer[0] = 4;
+                offset += data.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsProgrammableConfigDelegateV2 {
+                rule_set,
+                authorization_data,
+            } => {
+                buffer[0] = 5;
+                offset += rule_set.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsDataItemDelegateV2 {
+                data,
+                authorization_data,
+            } => {
+                buffer[0] = 6;
+                offset += data.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsCollectionItemDelegateV2 {
+                collection,
+                authorization_data,
+            } => {
+                buffer[0] = 7;
+                offset += collection.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+            Self::AsProgrammableConfigItemDelegateV2 {
+                rule_set,
+                authorization_data,
+            } => {
+                buffer[0] = 8;
+                offset += rule_set.serialize_to(&mut buffer[offset..]);
+                offset += authorization_data.serialize_to(&mut buffer[offset..]);
+            }
+        }
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + match self {
+            Self::V1 {
+                new_update_authority,
+                data,
+                primary_sale_happened,
+                is_mutable,
+                collection,
+                collection_details,
+                uses,
+                rule_set,
+                authorization_data,
+            } => {
+                new_update_authority.serialized_len()
+                    + data.serialized_len()
+                    + primary_sale_happened.serialized_len()
+                    + is_mutable.serialized_len()
+                    + collection.serialized_len()
+                    + collection_details.serialized_len()
+                    + uses.serialized_len()
+                    + rule_set.serialized_len()
+                    + authorization_data.serialized_len()
+            }
+            Self::AsUpdateAuthorityV2 {
+                new_update_authority,
+                data,
+                primary_sale_happened,
+                is_mutable,
+                collection,
+                collection_details,
+                uses,
+                rule_set,
+                token_standard,
+                authorization_data,
+            } => {
+                new_update_authority.serialized_len()
+                    + data.serialized_len()
+                    + primary_sale_happened.serialized_len()
+                    + is_mutable.serialized_len()
+                    + collection.serialized_len()
+                    + collection_details.serialized_len()
+                    + uses.serialized_len()
+                    + rule_set.serialized_len()
+                    + token_standard.serialized_len()
+                    + authorization_data.serialized_len()
+            }
+            Self::AsAuthorityItemDelegateV2 {
+                new_update_authority,
+                primary_sale_happened,
+                is_mutable,
+                token_standard,
+                authorization_data,
+            } => {
+                new_update_authority.serialized_len()
+                    + primary_sale_happened.serialized_len()
+                    + is_mutable.serialized_len()
+                    + token_standard.serialized_len()
+                    + authorization_data.serialized_len()
+            }
+            Self::AsCollectionDelegateV2 {
+                collection,
+                authorization_data,
+            }
+            | Self::AsCollectionItemDelegateV2 {
+                collection,
+                authorization_data,
+            } => collection.serialized_len() + authorization_data.serialized_len(),
+            Self::AsDataDelegateV2 {
+                data,
+                authorization_data,
+            }
+            | Self::AsDataItemDelegateV2 {
+                data,
+                authorization_data,
+            } => data.serialized_len() + authorization_data.serialized_len(),
+            Self::AsProgrammableConfigDelegateV2 {
+                rule_set,
+                authorization_data,
+            }
+            | Self::AsProgrammableConfigItemDelegateV2 {
+                rule_set,
+                authorization_data,
+            } => rule_set.serialized_len() + authorization_data.serialized_len(),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for UpdateArgs<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        let mut offset = 1;
+
+        // fields are read in the order they are written, like borsh
+        let args = match discriminator(bytes)? {
+            0 => Self::V1 {
+                new_update_authority: read(bytes, &mut offset)?,
+                data: read(bytes, &mut offset)?,
+                primary_sale_happened: read(bytes, &mut offset)?,
+                is_mutable: read(bytes, &mut offset)?,
+                collection: read(bytes, &mut offset)?,
+                collection_details: read(bytes, &mut offset)?,
+                uses: read(bytes, &mut offset)?,
+                rule_set: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            1 => Self::AsUpdateAuthorityV2 {
+                new_update_authority: read(bytes, &mut offset)?,
+                data: read(bytes, &mut offset)?,
+                primary_sale_happened: read(bytes, &mut offset)?,
+                is_mutable: read(bytes, &mut offset)?,
+                collection: read(bytes, &mut offset)?,
+                collection_details: read(bytes, &mut offset)?,
+                uses: read(bytes, &mut offset)?,
+                rule_set: read(bytes, &mut offset)?,
+                token_standard: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            2 => Self::AsAuthorityItemDelegateV2 {
+                new_update_authority: read(bytes, &mut offset)?,
+                primary_sale_happened: read(bytes, &mut offset)?,
+                is_mutable: read(bytes, &mut offset)?,
+                token_standard: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            3 => Self::AsCollectionDelegateV2 {
+                collection: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            4 => Self::AsDataDelegateV2 {
+                data: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            5 => Self::AsProgrammableConfigDelegateV2 {
+                rule_set: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            6 => Self::AsDataItemDelegateV2 {
+                data: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            7 => Self::AsCollectionItemDelegateV2 {
+                collection: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            8 => Self::AsProgrammableConfigItemDelegateV2 {
+                rule_set: read(bytes, &mut offset)?,
+                authorization_data: read(bytes, &mut offset)?,
+            },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok((args, offset))
+    }
+}
+
+pub struct UpdateInstructionData<'a> {
+    pub args: UpdateArgs<'a>,
+}
+
+impl<'a> Serialize for UpdateInstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Update.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for UpdateInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Update.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = UpdateArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
@@ -0,0 +1,73 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{authorization::AuthorizationData, discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+pub enum UseArgs<'a> {
+    V1 {
+        /// Required authorization data to validate the request.
+        authorization_data: Option<AuthorizationData<'a>>,
+    },
+}
+
+impl<'a> Serialize for UseArgs<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        match self {
+            Self::V1 { authorization_data } => {
+                buffer[0] = 0;
+                1 + authorization_data.serialize_to(&mut buffer[1..])
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        match self {
+            Self::V1 { authorization_data } => 1 + authorization_data.serialized_len(),
+        }
+    }
+}
+
+impl<'a> Deserialize<'a> for UseArgs<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => {
+                let (authorization_data, len) = Option::deserialize_from(&bytes[1..])?;
+                Ok((Self::V1 { authorization_data }, 1 + len))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct UseInstructionData<'a> {
+    pub args: UseArgs<'a>,
+}
+
+impl<'a> Serialize for UseInstructionData<'a> {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Use.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.args.serialized_len()
+    }
+}
+
+impl<'a> Deserialize<'a> for UseInstructionData<'a> {
+    fn deserialize_from(bytes: &'a [u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Use.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = UseArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
+    }
+}
@@ -1,21 +1,74 @@
-use crate::{data::Serialize, Instructions};
+use pinocchio::program_error::ProgramError;
 
-pub enum VerifyInstructionData {
+use crate::{
+    data::{discriminator, Deserialize, Serialize},
+    Instructions,
+};
+
+/// What is being verified or unverified, shared by Verify and Unverify
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VerificationArgs {
     CreatorV1,
     CollectionV1,
 }
 
-impl Serialize for VerifyInstructionData {
+impl Serialize for VerificationArgs {
     fn serialize_to(&self, buffer: &mut [u8]) -> usize {
-        buffer[0] = Instructions::Verify.to_u8();
-        let offset = 1;
-
-        // instruction data is actually and enum so write the first byte
-        buffer[offset] = match self {
+        buffer[0] = match self {
             Self::CreatorV1 => 0,
             Self::CollectionV1 => 1,
         };
 
-        return offset;
+        1
+    }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize<'_> for VerificationArgs {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        match discriminator(bytes)? {
+            0 => Ok((Self::CreatorV1, 1)),
+            1 => Ok((Self::CollectionV1, 1)),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct VerifyInstructionData {
+    pub args: VerificationArgs,
+}
+
+impl VerifyInstructionData {
+    /// discriminator + args
+    pub const MAX_LEN: usize = 2;
+}
+
+impl Serialize for VerifyInstructionData {
+    fn serialize_to(&self, buffer: &mut [u8]) -> usize {
+        buffer[0] = Instructions::Verify.to_u8();
+        let mut offset = 1;
+
+        offset += self.args.serialize_to(&mut buffer[offset..]);
+
+        offset
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::MAX_LEN
+    }
+}
+
+impl Deserialize<'_> for VerifyInstructionData {
+    fn deserialize_from(bytes: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if bytes.first() != Some(&Instructions::Verify.to_u8()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (args, len) = VerificationArgs::deserialize_from(&bytes[1..])?;
+
+        Ok((Self { args }, 1 + len))
     }
 }
@@ -0,0 +1,79 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    data::{
+        burn::BurnInstructionData, create::CreateAssetInstructionData,
+        create_master_edition_v3::CreateMasterEditionV3InstructionData,
+        create_metadata_account_v3::CreateMetadataAccountV3InstructionData,
+        delegate::DelegateInstructionData, lock::LockInstructionData,
+        migrate::MigrateInstructionData, mint::MintInstructionData, print::PrintInstructionData,
+        revoke::RevokeInstructionData, transfer::TransferInstructionData,
+        unlock::UnlockInstructionData, unverify::UnverifyInstructionData,
+        update::UpdateInstructionData, use_asset::UseInstructionData,
+        verify::VerifyInstructionData, Deserialize,
+    },
+    Instructions,
+};
+
+/// A decoded token metadata instruction, borrowing from its data
+pub enum TokenMetadataInstruction<'a> {
+    CreateMasterEditionV3(CreateMasterEditionV3InstructionData),
+    CreateMetadataAccountV3(CreateMetadataAccountV3InstructionData<'a>),
+    Burn(BurnInstructionData),
+    Create(CreateAssetInstructionData<'a>),
+    Mint(MintInstructionData<'a>),
+    Delegate(DelegateInstructionData<'a>),
+    Revoke(RevokeInstructionData),
+    Lock(LockInstructionData<'a>),
+    Unlock(UnlockInstructionData<'a>),
+    Migrate(MigrateInstructionData),
+    Transfer(TransferInstructionData<'a>),
+    Update(UpdateInstructionData<'a>),
+    Use(UseInstructionData<'a>),
+    Verify(VerifyInstructionData),
+    Unverify(UnverifyInstructionData),
+    Print(PrintInstructionData),
+    /// An instruction this crate doesn't support yet, with its discriminator.
+    /// The data is not checked at all
+    Unknown(u8),
+}
+
+/// Decodes the data of an instruction sent to the token metadata program.
+/// Like the program itself, the whole data has to be used
+pub fn decode_instruction(data: &[u8]) -> Result<TokenMetadataInstruction<'_>, ProgramError> {
+    let discriminator = *data.first().ok_or(ProgramError::InvalidInstructionData)?;
+
+    let instruction = match Instructions::from_u8(discriminator) {
+        Some(Instructions::CreateMasterEditionV3) => {
+            TokenMetadataInstruction::CreateMasterEditionV3(Deserialize::decode(data)?)
+        }
+        Some(Instructions::CreateMetadataAccountV3) => {
+            TokenMetadataInstruction::CreateMetadataAccountV3(Deserialize::decode(data)?)
+        }
+        Some(Instructions::Burn) => TokenMetadataInstruction::Burn(Deserialize::decode(data)?),
+        Some(Instructions::Create) => TokenMetadataInstruction::Create(Deserialize::decode(data)?),
+        Some(Instructions::Mint) => TokenMetadataInstruction::Mint(Deserialize::decode(data)?),
+        Some(Instructions::Delegate) => {
+            TokenMetadataInstruction::Delegate(Deserialize::decode(data)?)
+        }
+        Some(Instructions::Revoke) => TokenMetadataInstruction::Revoke(Deserialize::decode(data)?),
+        Some(Instructions::Lock) => TokenMetadataInstruction::Lock(Deserialize::decode(data)?),
+        Some(Instructions::Unlock) => TokenMetadataInstruction::Unlock(Deserialize::decode(data)?),
+        Some(Instructions::Migrate) => {
+            TokenMetadataInstruction::Migrate(Deserialize::decode(data)?)
+        }
+        Some(Instructions::Transfer) => {
+            TokenMetadataInstruction::Transfer(Deserialize::decode(data)?)
+        }
+        Some(Instructions::Update) => TokenMetadataInstruction::Update(Deserialize::decode(data)?),
+        Some(Instructions::Use) => TokenMetadataInstruction::Use(Deserialize::decode(data)?),
+        Some(Instructions::Verify) => TokenMetadataInstruction::Verify(Deserialize::decode(data)?),
+        Some(Instructions::Unverify) => {
+            TokenMetadataInstruction::Unverify(Deserialize::decode(data)?)
+        }
+        Some(Instructions::Print) => TokenMetadataInstruction::Print(Deserialize::decode(data)?),
+        None => TokenMetadataInstruction::Unknown(discriminator),
+    };
+
+    Ok(instruction)
+}
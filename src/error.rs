@@ -0,0 +1,33 @@
+use pinocchio::program_error::ProgramError;
+
+/// Errors returned by this crate, as `ProgramError::Custom`.
+///
+/// They start at 9000 so they can't be confused with token metadata's own errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PnftError {
+    /// The serialization buffer is too small for the instruction data
+    BufferTooSmall = 9000,
+    /// The token program is neither SPL Token nor Token-2022
+    InvalidTokenProgram = 9001,
+    /// A mint or token account doesn't belong to the token program that was passed
+    TokenProgramMismatch = 9002,
+    /// An account the instruction needs as a signer didn't sign
+    MissingSigner = 9003,
+    /// An account the instruction writes to isn't writable
+    NotWritable = 9004,
+    /// A token metadata account isn't owned by token metadata
+    InvalidOwner = 9005,
+    /// A program account isn't the expected program
+    InvalidProgramId = 9006,
+    /// An account isn't the token metadata PDA it should be
+    InvalidPda = 9007,
+    /// A string or list is too long for its u32 length prefix
+    LengthOverflow = 9008,
+}
+
+impl From<PnftError> for ProgramError {
+    fn from(error: PnftError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
@@ -1,11 +1,20 @@
 use pinocchio::{
     account_info::AccountInfo,
-    cpi::invoke_signed,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::data::{burn::BurnInstructionData, Serialize};
+use super::{
+    invoke_signed, metas,
+    validation::{check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{burn::BurnInstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+};
 
 /// Burn an asset
 ///
@@ -13,19 +22,18 @@ use crate::data::{burn::BurnInstructionData, Serialize};
 ///   0. `[WRITE, SIGNER]` Authority
 ///   1. `[WRITE, OPTIONAL]` Collection Metadata
 ///   2. `[WRITE]` Metadata
-///   3. `[SIGNER, OPTIONAL]` Authority
-///   4. `[WRITE, OPTIONAL]` Edition
-///   5. `[WRITE]` Mint
-///   6. `[WRITE]` Token Account
-///   7. `[WRITE, OPTIONAL]` Master Edition
-///   8. `[OPTIONAL]` Master Edition Mint
-///   9. `[OPTIONAL]` Master Edition Token Account
-///   10. `[WRITE, OPTIONAL]` Edition Marker
-///   11. `[WRITE, OPTIONAL]` Token Record
-///   12. `[]` System Program
-///   13. `[]` Sysvar Instructions
-///   14. `[]` SPL Token Program
-///   15. `[]` MPL Token Metadata
+///   3. `[WRITE, OPTIONAL]` Edition
+///   4. `[WRITE]` Mint
+///   5. `[WRITE]` Token Account
+///   6. `[WRITE, OPTIONAL]` Master Edition
+///   7. `[OPTIONAL]` Master Edition Mint
+///   8. `[OPTIONAL]` Master Edition Token Account
+///   9. `[WRITE, OPTIONAL]` Edition Marker
+///   10. `[WRITE, OPTIONAL]` Token Record
+///   11. `[]` System Program
+///   12. `[]` Sysvar Instructions
+///   13. `[]` SPL Token or Token-2022 Program
+///   14. `[]` MPL Token Metadata
 ///
 /// Accounts being optional is very cursed but mimics the behaviour of the official lib.
 /// Accounts set to None get replaced by mpl program's account.
@@ -33,7 +41,6 @@ pub struct Burn<'a> {
     pub authority: &'a AccountInfo,
     pub collection_metadata: Option<&'a AccountInfo>,
     pub metadata: &'a AccountInfo,
-    pub delegate_authority: Option<&'a AccountInfo>,
     pub edition: Option<&'a AccountInfo>,
     pub mint: &'a AccountInfo,
     pub token_account: &'a AccountInfo,
@@ -48,7 +55,18 @@ pub struct Burn<'a> {
     pub mpl_token_metadata: &'a AccountInfo,
 }
 
-impl Burn<'_> {
+impl<'a> Burn<'a> {
+    /// Makes sure the token program is SPL Token or Token-2022 and that the mint
+    /// and the token account belong to it
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = TokenProgram::check(self.spl_token_program)?;
+
+        token_program.check_owner(self.mint)?;
+        token_program.check_owner(self.token_account)?;
+
+        Ok(token_program)
+    }
+
     #[inline(always)]
     pub fn invoke(
         &self,
@@ -64,81 +82,112 @@ impl Burn<'_> {
         signers: &[Signer],
         serialization_buffer: &mut [u8],
     ) -> ProgramResult {
-        // account metadata
-        let account_metas: &[AccountMeta] = &[
-            AccountMeta::writable_signer(self.authority.key()),
-            match self.collection_metadata {
-                Some(cm) => AccountMeta::writable(cm.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable(self.metadata.key()),
-            match self.delegate_authority {
-                Some(da) => AccountMeta::readonly_signer(da.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.edition {
-                Some(edition) => AccountMeta::writable(edition.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable(self.mint.key()),
-            AccountMeta::writable(self.token_account.key()),
-            match self.master_edition {
-                Some(me) => AccountMeta::writable(me.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.master_edition_mint {
-                Some(mem) => AccountMeta::readonly(mem.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.master_edition_token_account {
-                Some(meta) => AccountMeta::readonly(meta.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.edition_marker {
-                Some(marker) => AccountMeta::writable(marker.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.token_record {
-                Some(record) => AccountMeta::writable(record.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.sysvar_instructions.key()),
-            AccountMeta::readonly(self.spl_token_program.key()),
-            AccountMeta::readonly(self.mpl_token_metadata.key()),
-        ];
-
-        let len = data.serialize_to(serialization_buffer);
-        let data = &serialization_buffer[..len];
-
-        let instruction = Instruction {
-            program_id: &crate::MPL_TOKEN_METADATA_ID,
-            accounts: &account_metas,
-            data,
-        };
-
-        invoke_signed(
-            &instruction,
-            &[
-                self.authority,
-                self.collection_metadata.unwrap_or(self.mpl_token_metadata),
-                self.metadata,
-                self.delegate_authority.unwrap_or(self.mpl_token_metadata),
-                self.edition.unwrap_or(self.mpl_token_metadata),
-                self.mint,
-                self.token_account,
-                self.master_edition.unwrap_or(self.mpl_token_metadata),
-                self.master_edition_mint.unwrap_or(self.mpl_token_metadata),
-                self.master_edition_token_account
-                    .unwrap_or(self.mpl_token_metadata),
-                self.edition_marker.unwrap_or(self.mpl_token_metadata),
-                self.token_record.unwrap_or(self.mpl_token_metadata),
-                self.system_program,
-                self.sysvar_instructions,
-                self.spl_token_program,
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        self.check_token_program()?;
+
+        check_mpl_owned(&[
+            Some(self.metadata),
+            self.collection_metadata,
+            self.edition,
+            self.master_edition,
+            self.edition_marker,
+            self.token_record,
+        ])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(edition) = self.edition {
+            validation.check_pda(|| pda::check_master_edition(edition, self.mint.key()))?;
+        }
+        if let Some(token_record) = self.token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(token_record, self.mint.key(), self.token_account.key())
+            })?;
+        }
+        if let (Some(master_edition), Some(master_edition_mint)) =
+            (self.master_edition, self.master_edition_mint)
+        {
+            validation.check_pda(|| {
+                pda::check_master_edition(master_edition, master_edition_mint.key())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 15] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 15] {
+        [
+            CpiAccount::writable_signer(self.authority),
+            CpiAccount::optional(
+                self.collection_metadata.map(CpiAccount::writable),
                 self.mpl_token_metadata,
-            ],
-            signers,
-        )
+            ),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.edition.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.mint),
+            CpiAccount::writable(self.token_account),
+            CpiAccount::optional(
+                self.master_edition.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.master_edition_mint.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.master_edition_token_account.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.edition_marker.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.spl_token_program),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &BurnInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &BurnInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
     }
 }
@@ -1,25 +1,30 @@
 use pinocchio::{
     account_info::AccountInfo,
-    cpi::invoke_signed,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::data::{create::CreateAssetInstructionData, Serialize};
+use super::{invoke_signed, metas, validation::check_programs, CpiAccount, Validation};
+use crate::{
+    data::{create::CreateAssetInstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+};
 
 /// Create an asset
 ///
 /// ### Accounts:
 ///   0. `[WRITE]` Metadata
 ///   1. `[WRITE, OPTIONAL]` Master Edition
-///   2. `[WRITE]` Mint
+///   2. `[WRITE, SIGNER if it doesn't exist yet]` Mint
 ///   3. `[SIGNER]` Authority
-///   3. `[SIGNER, WRITE]` Payer
-///   4. `[]` Update Authority
-///   5. `[]` System Program
-///   6. `[]` Sysvar Instructions
-///   7. `[OPTIONAL]` SPL Token Program
-///   8. `[]` MPL Metadata Program
+///   4. `[SIGNER, WRITE]` Payer
+///   5. `[]` Update Authority
+///   6. `[]` System Program
+///   7. `[]` Sysvar Instructions
+///   8. `[OPTIONAL]` SPL Token or Token-2022 Program, SPL Token if None
+///   9. `[]` MPL Metadata Program
 ///
 /// Accounts being optional is very cursed but mimics the behaviour of the official lib.
 /// Accounts set to None get replaced by mpl program's account.
@@ -36,7 +41,20 @@ pub struct CreateAsset<'a> {
     pub mpl_token_metadata: &'a AccountInfo,
 }
 
-impl CreateAsset<'_> {
+impl<'a> CreateAsset<'a> {
+    /// Makes sure the token program is SPL Token or Token-2022 and that the mint belongs to it,
+    /// if it was already created. Without a token program token metadata uses SPL Token
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = match self.token_program {
+            Some(token_program) => TokenProgram::check(token_program)?,
+            None => TokenProgram::SplToken,
+        };
+
+        token_program.check_owner_if_created(self.mint)?;
+
+        Ok(token_program)
+    }
+
     #[inline(always)]
     pub fn invoke(
         &self,
@@ -52,48 +70,73 @@ impl CreateAsset<'_> {
         signers: &[Signer],
         serialization_buffer: &mut [u8],
     ) -> ProgramResult {
-        // account metadata
-        let account_metas: &[AccountMeta] = &[
-            AccountMeta::writable(self.metadata.key()),
-            match self.master_edition {
-                Some(master_edition) => AccountMeta::writable(master_edition.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable(self.mint.key()),
-            AccountMeta::readonly_signer(self.authority.key()),
-            AccountMeta::writable_signer(self.payer.key()),
-            AccountMeta::readonly(self.update_authority.key()),
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.sysvar_instructions.key()),
-            match self.token_program {
-                Some(token_program) => AccountMeta::writable(token_program.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly(self.mpl_token_metadata.key()),
-        ];
+        let len = data.try_serialize_to(serialization_buffer)?;
 
-        let len = data.serialize_to(serialization_buffer);
-        let data = &serialization_buffer[..len];
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
 
-        let instruction = Instruction {
-            program_id: &crate::MPL_TOKEN_METADATA_ID,
-            accounts: &account_metas,
-            data,
-        };
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        self.check_token_program()?;
+
+        // metadata and master edition are created by the instruction
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(master_edition) = self.master_edition {
+            validation.check_pda(|| pda::check_master_edition(master_edition, self.mint.key()))?;
+        }
+
+        Ok(())
+    }
 
-        invoke_signed(
-            &instruction,
-            &[
-                self.metadata,
-                self.master_edition.unwrap_or(self.mpl_token_metadata),
-                self.mint,
-                self.authority,
-                self.payer,
-                self.update_authority,
-                self.system_program,
-                self.token_program.unwrap_or(self.mpl_token_metadata),
-            ],
-            signers,
-        )
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 10] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 10] {
+        [
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.master_edition.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            // the mint only signs when token metadata has to create it
+            CpiAccount::new(self.mint, true, self.mint.data_is_empty()),
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.update_authority),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::optional(
+                self.token_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &CreateAssetInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &CreateAssetInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
     }
 }
@@ -0,0 +1,138 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
+    sysvars::rent::RENT_ID,
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_mpl_owned, check_program},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{create_master_edition_v3::CreateMasterEditionV3InstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+    MPL_TOKEN_METADATA_ID, SYSTEM_PROGRAM_ID,
+};
+
+/// Create the master edition of a mint that already has its metadata, the legacy way.
+/// Token metadata takes over the mint and freeze authorities
+///
+/// ### Accounts:
+///   0. `[WRITE]` Master Edition
+///   1. `[WRITE]` Mint
+///   2. `[SIGNER]` Update Authority
+///   3. `[SIGNER]` Mint Authority
+///   4. `[SIGNER, WRITE]` Payer
+///   5. `[WRITE]` Metadata
+///   6. `[]` SPL Token or Token-2022 Program
+///   7. `[]` System Program
+///   8. `[OPTIONAL]` Rent Sysvar
+///   9. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct CreateMasterEditionV3<'a> {
+    pub master_edition: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub metadata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub rent: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> CreateMasterEditionV3<'a> {
+    /// Makes sure the token program is SPL Token or Token-2022 and that the mint belongs to it
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = TokenProgram::check(self.token_program)?;
+
+        token_program.check_owner(self.mint)?;
+
+        Ok(token_program)
+    }
+
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &CreateMasterEditionV3InstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &CreateMasterEditionV3InstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_program(self.system_program, &SYSTEM_PROGRAM_ID)?;
+        check_program(self.mpl_token_metadata, &MPL_TOKEN_METADATA_ID)?;
+        if let Some(rent) = self.rent {
+            check_program(rent, &RENT_ID)?;
+        }
+        self.check_token_program()?;
+
+        check_mpl_owned(&[Some(self.metadata)])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        // the master edition is created by the instruction
+        validation.check_pda(|| pda::check_master_edition(self.master_edition, self.mint.key()))?;
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 10] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 10] {
+        [
+            CpiAccount::writable(self.master_edition),
+            CpiAccount::writable(self.mint),
+            CpiAccount::readonly_signer(self.update_authority),
+            CpiAccount::readonly_signer(self.mint_authority),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::readonly(self.token_program),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::optional(self.rent.map(CpiAccount::readonly), self.mpl_token_metadata),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &CreateMasterEditionV3InstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &CreateMasterEditionV3InstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -0,0 +1,122 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    sysvars::rent::RENT_ID,
+    ProgramResult,
+};
+
+use super::{invoke_signed, metas, validation::check_program, CpiAccount, Validation};
+use crate::{
+    data::{create_metadata_account_v3::CreateMetadataAccountV3InstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+    MPL_TOKEN_METADATA_ID, SYSTEM_PROGRAM_ID,
+};
+
+/// Create the metadata of a mint, the legacy way (fungibles, SFTs and regular NFTs)
+///
+/// ### Accounts:
+///   0. `[WRITE]` Metadata
+///   1. `[]` Mint
+///   2. `[SIGNER]` Mint Authority
+///   3. `[SIGNER, WRITE]` Payer
+///   4. `[SIGNER if update_authority_is_signer]` Update Authority
+///   5. `[]` System Program
+///   6. `[OPTIONAL]` Rent Sysvar
+///   7. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct CreateMetadataAccountV3<'a> {
+    pub metadata: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    /// Creators can only be verified at creation if the update authority signs
+    pub update_authority_is_signer: bool,
+    pub system_program: &'a AccountInfo,
+    pub rent: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> CreateMetadataAccountV3<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &CreateMetadataAccountV3InstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &CreateMetadataAccountV3InstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_program(self.system_program, &SYSTEM_PROGRAM_ID)?;
+        check_program(self.mpl_token_metadata, &MPL_TOKEN_METADATA_ID)?;
+        if let Some(rent) = self.rent {
+            check_program(rent, &RENT_ID)?;
+        }
+
+        // there is no token program account, the mint can belong to either
+        TokenProgram::from_owner(self.mint)?;
+
+        // the metadata is created by the instruction
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 8] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 8] {
+        [
+            CpiAccount::writable(self.metadata),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::readonly_signer(self.mint_authority),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::new(
+                self.update_authority,
+                false,
+                self.update_authority_is_signer,
+            ),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::optional(self.rent.map(CpiAccount::readonly), self.mpl_token_metadata),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &CreateMetadataAccountV3InstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &CreateMetadataAccountV3InstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
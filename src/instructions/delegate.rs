@@ -0,0 +1,205 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_auth_rules_program, check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{delegate::DelegateInstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+};
+
+/// Accounts of Delegate and Revoke, which take the same ones
+///
+/// ### Accounts:
+///   0. `[WRITE, OPTIONAL]` Delegate Record
+///   1. `[]` Delegate
+///   2. `[WRITE]` Metadata
+///   3. `[OPTIONAL]` Master Edition
+///   4. `[WRITE, OPTIONAL]` Token Record
+///   5. `[]` Mint
+///   6. `[WRITE, OPTIONAL]` Token Account
+///   7. `[SIGNER]` Authority (update authority or token owner)
+///   8. `[SIGNER, WRITE]` Payer
+///   9. `[]` System Program
+///   10. `[]` Sysvar Instructions
+///   11. `[OPTIONAL]` SPL Token Program
+///   12. `[OPTIONAL]` Auth Rules Program
+///   13. `[OPTIONAL]` Auth Rules Account
+///   14. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct DelegateAccounts<'a> {
+    pub delegate_record: Option<&'a AccountInfo>,
+    pub delegate: &'a AccountInfo,
+    pub metadata: &'a AccountInfo,
+    pub master_edition: Option<&'a AccountInfo>,
+    pub token_record: Option<&'a AccountInfo>,
+    pub mint: &'a AccountInfo,
+    pub token_account: Option<&'a AccountInfo>,
+    pub authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub spl_token_program: Option<&'a AccountInfo>,
+    pub auth_rules_program: Option<&'a AccountInfo>,
+    pub auth_rules: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> DelegateAccounts<'a> {
+    /// Serializes `data`, Delegate or Revoke, and sends it with the accounts
+    pub(super) fn invoke_signed_with(
+        &self,
+        data: &impl Serialize,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`.
+    /// The delegate record isn't checked, Delegate creates it
+    pub(super) fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_auth_rules_program(self.auth_rules_program)?;
+
+        if let Some(spl_token_program) = self.spl_token_program {
+            let token_program = TokenProgram::check(spl_token_program)?;
+            token_program.check_owner(self.mint)?;
+            if let Some(token_account) = self.token_account {
+                token_program.check_owner(token_account)?;
+            }
+        }
+
+        check_mpl_owned(&[Some(self.metadata), self.master_edition, self.token_record])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(master_edition) = self.master_edition {
+            validation.check_pda(|| pda::check_master_edition(master_edition, self.mint.key()))?;
+        }
+        if let (Some(token_record), Some(token_account)) = (self.token_record, self.token_account) {
+            validation.check_pda(|| {
+                pda::check_token_record(token_record, self.mint.key(), token_account.key())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub(super) fn account_metas(&self) -> [AccountMeta<'a>; 15] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 15] {
+        [
+            CpiAccount::optional(
+                self.delegate_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.delegate),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.master_edition.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::optional(
+                self.token_account.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::optional(
+                self.spl_token_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+}
+
+/// Create a delegate for an asset, see `DelegateAccounts` for the accounts
+pub struct Delegate<'a> {
+    pub accounts: DelegateAccounts<'a>,
+}
+
+impl<'a> Delegate<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &DelegateInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    #[inline(always)]
+    pub fn invoke_signed(
+        &self,
+        data: &DelegateInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.accounts
+            .invoke_signed_with(data, signers, serialization_buffer)
+    }
+
+    /// Checks the accounts against the `DelegateAccounts` table, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        self.accounts.validate(validation)
+    }
+
+    /// Account metas of the CPI, see `DelegateAccounts` for the order
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 15] {
+        self.accounts.account_metas()
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &DelegateInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &DelegateInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
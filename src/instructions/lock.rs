@@ -0,0 +1,196 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_auth_rules_program, check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{lock::LockInstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+};
+
+/// Accounts of Lock and Unlock, which take the same ones
+///
+/// ### Accounts:
+///   0. `[SIGNER]` Authority (delegate or freeze authority)
+///   1. `[OPTIONAL]` Token Owner
+///   2. `[WRITE]` Token Account
+///   3. `[]` Mint
+///   4. `[WRITE]` Metadata
+///   5. `[OPTIONAL]` Edition
+///   6. `[WRITE, OPTIONAL]` Token Record
+///   7. `[SIGNER, WRITE]` Payer
+///   8. `[]` System Program
+///   9. `[]` Sysvar Instructions
+///   10. `[OPTIONAL]` SPL Token Program
+///   11. `[OPTIONAL]` Auth Rules Program
+///   12. `[OPTIONAL]` Auth Rules Account
+///   13. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct LockAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub token_owner: Option<&'a AccountInfo>,
+    pub token_account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub metadata: &'a AccountInfo,
+    pub edition: Option<&'a AccountInfo>,
+    pub token_record: Option<&'a AccountInfo>,
+    pub payer: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub spl_token_program: Option<&'a AccountInfo>,
+    pub auth_rules_program: Option<&'a AccountInfo>,
+    pub auth_rules: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> LockAccounts<'a> {
+    /// Serializes `data`, Lock or Unlock, and sends it with the accounts
+    pub(super) fn invoke_signed_with(
+        &self,
+        data: &impl Serialize,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub(super) fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_auth_rules_program(self.auth_rules_program)?;
+
+        if let Some(spl_token_program) = self.spl_token_program {
+            let token_program = TokenProgram::check(spl_token_program)?;
+            token_program.check_owner(self.mint)?;
+            token_program.check_owner(self.token_account)?;
+        }
+
+        check_mpl_owned(&[Some(self.metadata), self.edition, self.token_record])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(edition) = self.edition {
+            validation.check_pda(|| pda::check_master_edition(edition, self.mint.key()))?;
+        }
+        if let Some(token_record) = self.token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(token_record, self.mint.key(), self.token_account.key())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub(super) fn account_metas(&self) -> [AccountMeta<'a>; 14] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 14] {
+        [
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::optional(
+                self.token_owner.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.token_account),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.edition.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::optional(
+                self.spl_token_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+}
+
+/// Lock an asset (freezes it in the owner's wallet), see `LockAccounts` for the accounts
+pub struct Lock<'a> {
+    pub accounts: LockAccounts<'a>,
+}
+
+impl<'a> Lock<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &LockInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    #[inline(always)]
+    pub fn invoke_signed(
+        &self,
+        data: &LockInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.accounts
+            .invoke_signed_with(data, signers, serialization_buffer)
+    }
+
+    /// Checks the accounts against the `LockAccounts` table, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        self.accounts.validate(validation)
+    }
+
+    /// Account metas of the CPI, see `LockAccounts` for the order
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 14] {
+        self.accounts.account_metas()
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &LockInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &LockInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -0,0 +1,169 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{
+        check_auth_rules_program, check_mpl_owned, check_mpl_owned_if_created, check_program,
+        check_programs,
+    },
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{migrate::MigrateInstructionData, Serialize},
+    pda,
+    token_program::SPL_TOKEN_ID,
+};
+
+/// Migrate an asset, to a programmable NFT for `MigrationType::ProgrammableV1`
+///
+/// ### Accounts:
+///   0. `[WRITE]` Metadata
+///   1. `[WRITE]` Edition
+///   2. `[WRITE]` Token Account
+///   3. `[]` Token Owner
+///   4. `[]` Mint
+///   5. `[SIGNER, WRITE]` Payer
+///   6. `[SIGNER]` Authority
+///   7. `[]` Collection Metadata
+///   8. `[OPTIONAL]` Delegate Record
+///   9. `[WRITE]` Token Record
+///   10. `[]` System Program
+///   11. `[]` Sysvar Instructions
+///   12. `[]` SPL Token Program
+///   13. `[OPTIONAL]` Auth Rules Program
+///   14. `[OPTIONAL]` Auth Rules Account
+///   15. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct Migrate<'a> {
+    pub metadata: &'a AccountInfo,
+    pub edition: &'a AccountInfo,
+    pub token: &'a AccountInfo,
+    pub token_owner: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub collection_metadata: &'a AccountInfo,
+    /// Collection delegate record of the authority, only when the authority is a delegate
+    /// of the collection instead of the update authority of the asset
+    pub delegate_record: Option<&'a AccountInfo>,
+    /// Created by the migration, the PDA of the mint and the token account
+    pub token_record: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub spl_token_program: &'a AccountInfo,
+    pub auth_rules_program: Option<&'a AccountInfo>,
+    pub auth_rules: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> Migrate<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &MigrateInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &MigrateInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`.
+    /// Migrate only supports SPL Token, not Token-2022
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_program(self.spl_token_program, &SPL_TOKEN_ID)?;
+        check_auth_rules_program(self.auth_rules_program)?;
+
+        check_mpl_owned(&[
+            Some(self.metadata),
+            Some(self.edition),
+            Some(self.collection_metadata),
+            self.delegate_record,
+        ])?;
+        // the token record is created by the migration
+        check_mpl_owned_if_created(&[Some(self.token_record)])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        validation.check_pda(|| pda::check_master_edition(self.edition, self.mint.key()))?;
+        validation.check_pda(|| {
+            pda::check_token_record(self.token_record, self.mint.key(), self.token.key())
+        })?;
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 16] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 16] {
+        [
+            CpiAccount::writable(self.metadata),
+            CpiAccount::writable(self.edition),
+            CpiAccount::writable(self.token),
+            CpiAccount::readonly(self.token_owner),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::readonly(self.collection_metadata),
+            CpiAccount::optional(
+                self.delegate_record.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.token_record),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.spl_token_program),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &MigrateInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &MigrateInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
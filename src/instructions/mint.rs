@@ -1,11 +1,23 @@
 use pinocchio::{
     account_info::AccountInfo,
-    cpi::invoke_signed,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::data::{mint::MintInstructionData, Serialize};
+use super::{
+    invoke_signed, metas,
+    validation::{
+        check_auth_rules_program, check_mpl_owned, check_mpl_owned_if_created, check_program,
+        check_programs,
+    },
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{mint::MintInstructionData, Serialize},
+    pda,
+    token_program::{TokenProgram, ASSOCIATED_TOKEN_PROGRAM_ID},
+};
 
 /// Mint an asset (use after Create)
 ///
@@ -21,7 +33,7 @@ use crate::data::{mint::MintInstructionData, Serialize};
 ///   8. `[SIGNER, WRITE]` Payer
 ///   9. `[]` System Program
 ///   10. `[]` Sysvar Instructions
-///   11. `[]` SPL Token Program
+///   11. `[]` SPL Token or Token-2022 Program
 ///   12. `[]` SPL Associated Token Program
 ///   13. `[OPTIONAL]` Auth Rules Program
 ///   14. `[OPTIONAL]` Auth Rules Account
@@ -48,7 +60,18 @@ pub struct MintAsset<'a> {
     pub mpl_token_metadata: &'a AccountInfo,
 }
 
-impl MintAsset<'_> {
+impl<'a> MintAsset<'a> {
+    /// Makes sure the token program is SPL Token or Token-2022 and that the mint
+    /// and the token account (if it was already created) belong to it
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = TokenProgram::check(self.token_program)?;
+
+        token_program.check_owner(self.mint)?;
+        token_program.check_owner_if_created(self.token_account)?;
+
+        Ok(token_program)
+    }
+
     #[inline(always)]
     pub fn invoke(
         &self,
@@ -64,75 +87,103 @@ impl MintAsset<'_> {
         signers: &[Signer],
         serialization_buffer: &mut [u8],
     ) -> ProgramResult {
-        // account metadata
-        let account_metas: &[AccountMeta] = &[
-            AccountMeta::writable(self.token_account.key()),
-            match self.owner {
-                Some(owner) => AccountMeta::readonly(owner.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly(self.metadata.key()),
-            match self.master_edition {
-                Some(edition) => AccountMeta::writable(edition.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.token_record {
-                Some(record) => AccountMeta::writable(record.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable(self.mint.key()),
-            AccountMeta::readonly_signer(self.authority.key()),
-            match self.metadata_delegate_record {
-                Some(delegate) => AccountMeta::readonly(delegate.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable_signer(self.payer.key()),
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.sysvar_instructions.key()),
-            AccountMeta::readonly(self.token_program.key()),
-            AccountMeta::readonly(self.associated_token_program.key()),
-            match self.auth_rules_program {
-                Some(program) => AccountMeta::readonly(program.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.auth_rules {
-                Some(rules) => AccountMeta::readonly(rules.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly(self.mpl_token_metadata.key()),
-        ];
-
-        let len = data.serialize_to(serialization_buffer);
-        let data = &serialization_buffer[..len];
-
-        let instruction = Instruction {
-            program_id: &crate::MPL_TOKEN_METADATA_ID,
-            accounts: &account_metas,
-            data,
-        };
-
-        invoke_signed(
-            &instruction,
-            &[
-                self.token_account,
-                self.owner.unwrap_or(self.mpl_token_metadata),
-                self.metadata,
-                self.master_edition.unwrap_or(self.mpl_token_metadata),
-                self.token_record.unwrap_or(self.mpl_token_metadata),
-                self.mint,
-                self.authority,
-                self.metadata_delegate_record
-                    .unwrap_or(self.mpl_token_metadata),
-                self.payer,
-                self.system_program,
-                self.sysvar_instructions,
-                self.token_program,
-                self.associated_token_program,
-                self.auth_rules_program.unwrap_or(self.mpl_token_metadata),
-                self.auth_rules.unwrap_or(self.mpl_token_metadata),
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_program(self.associated_token_program, &ASSOCIATED_TOKEN_PROGRAM_ID)?;
+        check_auth_rules_program(self.auth_rules_program)?;
+        self.check_token_program()?;
+
+        check_mpl_owned(&[
+            Some(self.metadata),
+            self.master_edition,
+            self.metadata_delegate_record,
+        ])?;
+        check_mpl_owned_if_created(&[self.token_record])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(master_edition) = self.master_edition {
+            validation.check_pda(|| pda::check_master_edition(master_edition, self.mint.key()))?;
+        }
+        if let Some(token_record) = self.token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(token_record, self.mint.key(), self.token_account.key())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 16] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 16] {
+        [
+            CpiAccount::writable(self.token_account),
+            CpiAccount::optional(
+                self.owner.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.metadata),
+            CpiAccount::optional(
+                self.master_edition.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.mint),
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::optional(
+                self.metadata_delegate_record.map(CpiAccount::readonly),
                 self.mpl_token_metadata,
-            ],
-            signers,
-        )
+            ),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.token_program),
+            CpiAccount::readonly(self.associated_token_program),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &MintInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &MintInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
     }
 }
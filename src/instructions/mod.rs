@@ -1,5 +1,119 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi,
+    instruction::{AccountMeta, Instruction, Signer},
+    ProgramResult,
+};
+
 pub mod burn;
 pub mod create;
+pub mod create_master_edition_v3;
+pub mod create_metadata_account_v3;
+pub mod delegate;
+pub mod lock;
+pub mod migrate;
 pub mod mint;
+pub mod print;
+pub mod revoke;
 pub mod transfer;
+pub mod unlock;
+pub mod unverify;
+pub mod update;
+pub mod use_asset;
 pub mod verify;
+
+mod validation;
+
+pub use validation::Validation;
+
+/// An account passed to token metadata.
+/// Both the account meta and the account info are built from it, so they can't get out of sync
+#[derive(Clone, Copy)]
+pub(crate) struct CpiAccount<'a> {
+    info: &'a AccountInfo,
+    writable: bool,
+    signer: bool,
+}
+
+impl<'a> CpiAccount<'a> {
+    #[inline(always)]
+    pub fn new(info: &'a AccountInfo, writable: bool, signer: bool) -> Self {
+        Self {
+            info,
+            writable,
+            signer,
+        }
+    }
+
+    #[inline(always)]
+    pub fn readonly(info: &'a AccountInfo) -> Self {
+        Self {
+            info,
+            writable: false,
+            signer: false,
+        }
+    }
+
+    #[inline(always)]
+    pub fn writable(info: &'a AccountInfo) -> Self {
+        Self {
+            info,
+            writable: true,
+            signer: false,
+        }
+    }
+
+    #[inline(always)]
+    pub fn readonly_signer(info: &'a AccountInfo) -> Self {
+        Self {
+            info,
+            writable: false,
+            signer: true,
+        }
+    }
+
+    #[inline(always)]
+    pub fn writable_signer(info: &'a AccountInfo) -> Self {
+        Self {
+            info,
+            writable: true,
+            signer: true,
+        }
+    }
+
+    /// Optional accounts set to None get replaced by mpl program's account, read only
+    #[inline(always)]
+    pub fn optional(account: Option<Self>, mpl_token_metadata: &'a AccountInfo) -> Self {
+        account.unwrap_or(Self::readonly(mpl_token_metadata))
+    }
+
+    #[inline(always)]
+    fn meta(&self) -> AccountMeta<'a> {
+        AccountMeta::new(self.info.key(), self.writable, self.signer)
+    }
+}
+
+/// Account metas of the accounts, in the same order
+#[inline(always)]
+pub(crate) fn metas<'a, const N: usize>(accounts: &[CpiAccount<'a>; N]) -> [AccountMeta<'a>; N] {
+    core::array::from_fn(|i| accounts[i].meta())
+}
+
+/// Invokes token metadata with the accounts in the given order
+#[inline(always)]
+pub(crate) fn invoke_signed<const N: usize>(
+    accounts: &[CpiAccount; N],
+    data: &[u8],
+    signers: &[Signer],
+) -> ProgramResult {
+    let account_metas = metas(accounts);
+    let account_infos: [&AccountInfo; N] = core::array::from_fn(|i| accounts[i].info);
+
+    let instruction = Instruction {
+        program_id: &crate::MPL_TOKEN_METADATA_ID,
+        accounts: &account_metas,
+        data,
+    };
+
+    cpi::invoke_signed(&instruction, &account_infos, signers)
+}
@@ -0,0 +1,206 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_mpl_owned, check_mpl_owned_if_created, check_program, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{print::PrintInstructionData, Serialize},
+    pda,
+    token_program::{TokenProgram, ASSOCIATED_TOKEN_PROGRAM_ID},
+};
+
+/// Print a numbered edition from a master edition
+///
+/// ### Accounts:
+///   0. `[WRITE]` Edition Metadata
+///   1. `[WRITE]` Edition
+///   2. `[WRITE, SIGNER if it doesn't exist yet]` Edition Mint
+///   3. `[]` Edition Token Account Owner
+///   4. `[WRITE]` Edition Token Account
+///   5. `[SIGNER]` Edition Mint Authority
+///   6. `[OPTIONAL, WRITE]` Edition Token Record
+///   7. `[WRITE]` Master Edition
+///   8. `[WRITE]` Edition Marker PDA
+///   9. `[SIGNER, WRITE]` Payer
+///   10. `[SIGNER if master_token_account_owner_is_signer]` Master Token Account Owner
+///   11. `[]` Master Token Account
+///   12. `[]` Master Metadata
+///   13. `[]` Update Authority
+///   14. `[]` SPL Token or Token-2022 Program
+///   15. `[]` SPL Associated Token Program
+///   16. `[]` Sysvar Instructions
+///   17. `[]` System Program
+///   18. `[OPTIONAL]` Holder Delegate Record
+///   19. `[OPTIONAL, SIGNER]` Delegate
+///   20. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct Print<'a> {
+    pub edition_metadata: &'a AccountInfo,
+    pub edition: &'a AccountInfo,
+    pub edition_mint: &'a AccountInfo,
+    pub edition_token_account_owner: &'a AccountInfo,
+    pub edition_token_account: &'a AccountInfo,
+    pub edition_mint_authority: &'a AccountInfo,
+    pub edition_token_record: Option<&'a AccountInfo>,
+    pub master_edition: &'a AccountInfo,
+    pub edition_marker: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub master_token_account_owner: &'a AccountInfo,
+    /// Only `PrintArgs::V2` lets a holder delegate print in place of the owner
+    pub master_token_account_owner_is_signer: bool,
+    pub master_token_account: &'a AccountInfo,
+    pub master_metadata: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub spl_token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    /// Print delegate record of `delegate`, for `PrintArgs::V2`
+    pub holder_delegate_record: Option<&'a AccountInfo>,
+    pub delegate: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> Print<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &PrintInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &PrintInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Makes sure the token program is SPL Token or Token-2022 and that the master token account
+    /// and the edition mint and token account (if they were already created) belong to it
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = TokenProgram::check(self.spl_token_program)?;
+
+        token_program.check_owner(self.master_token_account)?;
+        token_program.check_owner_if_created(self.edition_mint)?;
+        token_program.check_owner_if_created(self.edition_token_account)?;
+
+        Ok(token_program)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`.
+    /// The edition marker isn't derived, its seeds need the master edition's mint
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_program(self.associated_token_program, &ASSOCIATED_TOKEN_PROGRAM_ID)?;
+        self.check_token_program()?;
+
+        check_mpl_owned(&[
+            Some(self.master_edition),
+            Some(self.master_metadata),
+            self.holder_delegate_record,
+        ])?;
+        check_mpl_owned_if_created(&[Some(self.edition_marker), self.edition_token_record])?;
+
+        // the edition accounts are created by the instruction
+        validation
+            .check_pda(|| pda::check_metadata(self.edition_metadata, self.edition_mint.key()))?;
+        validation
+            .check_pda(|| pda::check_master_edition(self.edition, self.edition_mint.key()))?;
+        if let Some(edition_token_record) = self.edition_token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(
+                    edition_token_record,
+                    self.edition_mint.key(),
+                    self.edition_token_account.key(),
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 21] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 21] {
+        [
+            CpiAccount::writable(self.edition_metadata),
+            CpiAccount::writable(self.edition),
+            // the mint only signs when token metadata has to create it
+            CpiAccount::new(self.edition_mint, true, self.edition_mint.data_is_empty()),
+            CpiAccount::readonly(self.edition_token_account_owner),
+            CpiAccount::writable(self.edition_token_account),
+            CpiAccount::readonly_signer(self.edition_mint_authority),
+            CpiAccount::optional(
+                self.edition_token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.master_edition),
+            CpiAccount::writable(self.edition_marker),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::new(
+                self.master_token_account_owner,
+                false,
+                self.master_token_account_owner_is_signer,
+            ),
+            CpiAccount::readonly(self.master_token_account),
+            CpiAccount::readonly(self.master_metadata),
+            CpiAccount::readonly(self.update_authority),
+            CpiAccount::readonly(self.spl_token_program),
+            CpiAccount::readonly(self.associated_token_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::optional(
+                self.holder_delegate_record.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.delegate.map(CpiAccount::readonly_signer),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &PrintInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &PrintInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -0,0 +1,67 @@
+use pinocchio::{
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{delegate::DelegateAccounts, validation::check_mpl_owned, Validation};
+use crate::data::revoke::RevokeInstructionData;
+#[cfg(feature = "alloc")]
+use crate::data::Serialize;
+
+/// Revoke a delegate of an asset, see `DelegateAccounts` for the accounts
+pub struct Revoke<'a> {
+    pub accounts: DelegateAccounts<'a>,
+}
+
+impl<'a> Revoke<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &RevokeInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    #[inline(always)]
+    pub fn invoke_signed(
+        &self,
+        data: &RevokeInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.accounts
+            .invoke_signed_with(data, signers, serialization_buffer)
+    }
+
+    /// Checks the accounts against the `DelegateAccounts` table, see `Validation`.
+    /// Unlike Delegate, the delegate record has to exist already
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        self.accounts.validate(validation)?;
+
+        check_mpl_owned(&[self.accounts.delegate_record])
+    }
+
+    /// Account metas of the CPI, see `DelegateAccounts` for the order
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 15] {
+        self.accounts.account_metas()
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &RevokeInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &RevokeInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -1,20 +1,32 @@
 use pinocchio::{
     account_info::AccountInfo,
-    cpi::invoke_signed,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{AccountMeta, Signer},
+    program_error::ProgramError,
     ProgramResult,
 };
 
-use crate::data::{transfer::TransferInstructionData, Serialize};
+use super::{
+    invoke_signed, metas,
+    validation::{
+        check_auth_rules_program, check_mpl_owned, check_mpl_owned_if_created, check_program,
+        check_programs,
+    },
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{transfer::TransferInstructionData, Serialize},
+    pda,
+    token_program::{TokenProgram, ASSOCIATED_TOKEN_PROGRAM_ID},
+};
 
 /// Transfer an asset
 ///
 /// ### Accounts:
 ///   0. `[WRITE]` Token Account
-///   1. `[]` Owner
+///   1. `[]` Token Owner
 ///   2. `[WRITE]` Destination Token Account
-///   3. `[]` New Owner
-///   4. `[]` mint
+///   3. `[]` Destination Owner
+///   4. `[]` Mint
 ///   5. `[WRITE]` Metadata
 ///   6. `[OPTIONAL]` Edition
 ///   7. `[OPTIONAL, WRITE]` Owner Token Record
@@ -23,9 +35,9 @@ use crate::data::{transfer::TransferInstructionData, Serialize};
 ///   10. `[SIGNER, WRITE]` Payer
 ///   11. `[]` System Program
 ///   12. `[]` Sysvar Instructions
-///   13. `[]` SPL Token Program
+///   13. `[]` SPL Token or Token-2022 Program
 ///   14. `[]` SPL Associated Token Program
-///   15. `[]` Auth Rules Program. it says optional but doesn't work without it......
+///   15. `[OPTIONAL]` Auth Rules Program
 ///   16. `[OPTIONAL]` Auth Rules Account
 ///   17. `[]` MPL Token Metadata
 ///
@@ -47,12 +59,24 @@ pub struct Transfer<'a> {
     pub sysvar_instructions: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
     pub associated_token_program: &'a AccountInfo,
-    pub auth_rules_program: &'a AccountInfo,
+    pub auth_rules_program: Option<&'a AccountInfo>,
     pub auth_rules: Option<&'a AccountInfo>,
     pub mpl_token_metadata: &'a AccountInfo,
 }
 
-impl Transfer<'_> {
+impl<'a> Transfer<'a> {
+    /// Makes sure the token program is SPL Token or Token-2022 and that the mint
+    /// and both token accounts (the destination if it was already created) belong to it
+    pub fn check_token_program(&self) -> Result<TokenProgram, ProgramError> {
+        let token_program = TokenProgram::check(self.token_program)?;
+
+        token_program.check_owner(self.mint)?;
+        token_program.check_owner(self.src_token_account)?;
+        token_program.check_owner_if_created(self.dest_token_account)?;
+
+        Ok(token_program)
+    }
+
     #[inline(always)]
     pub fn invoke(
         &self,
@@ -68,70 +92,111 @@ impl Transfer<'_> {
         signers: &[Signer],
         serialization_buffer: &mut [u8],
     ) -> ProgramResult {
-        // account metadata
-        let account_metas: &[AccountMeta] = &[
-            AccountMeta::writable(self.src_token_account.key()),
-            AccountMeta::readonly(self.owner.key()),
-            AccountMeta::writable(self.dest_token_account.key()),
-            AccountMeta::readonly(self.new_owner.key()),
-            AccountMeta::readonly(self.mint.key()),
-            AccountMeta::writable(self.metadata.key()),
-            match self.edition {
-                Some(edition) => AccountMeta::readonly(edition.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.owner_token_record {
-                Some(owner_token_record) => AccountMeta::writable(owner_token_record.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.dest_token_record {
-                Some(dest_token_record) => AccountMeta::writable(dest_token_record.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly_signer(self.authority.key()),
-            AccountMeta::writable_signer(self.payer.key()),
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.sysvar_instructions.key()),
-            AccountMeta::readonly(self.token_program.key()),
-            AccountMeta::readonly(self.associated_token_program.key()),
-            AccountMeta::readonly(self.auth_rules_program.key()),
-            match self.auth_rules {
-                Some(auth_rules) => AccountMeta::readonly(auth_rules.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-        ];
-
-        let len = data.serialize_to(serialization_buffer);
-        let data = &serialization_buffer[..len];
-
-        let instruction = Instruction {
-            program_id: &crate::MPL_TOKEN_METADATA_ID,
-            accounts: &account_metas,
-            data,
-        };
-
-        invoke_signed(
-            &instruction,
-            &[
-                self.src_token_account,
-                self.owner,
-                self.dest_token_account,
-                self.new_owner,
-                self.mint,
-                self.metadata,
-                self.edition.unwrap_or(self.mpl_token_metadata),
-                self.owner_token_record.unwrap_or(self.mpl_token_metadata),
-                self.dest_token_record.unwrap_or(self.mpl_token_metadata),
-                self.authority,
-                self.payer,
-                self.system_program,
-                self.sysvar_instructions,
-                self.token_program,
-                self.associated_token_program,
-                self.auth_rules_program,
-                self.auth_rules.unwrap_or(self.mpl_token_metadata),
-            ],
-            signers,
-        )
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_program(self.associated_token_program, &ASSOCIATED_TOKEN_PROGRAM_ID)?;
+        check_auth_rules_program(self.auth_rules_program)?;
+        self.check_token_program()?;
+
+        check_mpl_owned(&[Some(self.metadata), self.edition, self.owner_token_record])?;
+        check_mpl_owned_if_created(&[self.dest_token_record])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(edition) = self.edition {
+            validation.check_pda(|| pda::check_master_edition(edition, self.mint.key()))?;
+        }
+        if let Some(owner_token_record) = self.owner_token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(
+                    owner_token_record,
+                    self.mint.key(),
+                    self.src_token_account.key(),
+                )
+            })?;
+        }
+        if let Some(dest_token_record) = self.dest_token_record {
+            validation.check_pda(|| {
+                pda::check_token_record(
+                    dest_token_record,
+                    self.mint.key(),
+                    self.dest_token_account.key(),
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 18] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 18] {
+        [
+            CpiAccount::writable(self.src_token_account),
+            CpiAccount::readonly(self.owner),
+            CpiAccount::writable(self.dest_token_account),
+            CpiAccount::readonly(self.new_owner),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.edition.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.owner_token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.dest_token_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.token_program),
+            CpiAccount::readonly(self.associated_token_program),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &TransferInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &TransferInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
     }
 }
@@ -0,0 +1,64 @@
+use pinocchio::{
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{lock::LockAccounts, Validation};
+use crate::data::unlock::UnlockInstructionData;
+#[cfg(feature = "alloc")]
+use crate::data::Serialize;
+
+/// Unlock an asset (thaws it in the owner's wallet), see `LockAccounts` for the accounts
+pub struct Unlock<'a> {
+    pub accounts: LockAccounts<'a>,
+}
+
+impl<'a> Unlock<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &UnlockInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    #[inline(always)]
+    pub fn invoke_signed(
+        &self,
+        data: &UnlockInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.accounts
+            .invoke_signed_with(data, signers, serialization_buffer)
+    }
+
+    /// Checks the accounts against the `LockAccounts` table, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        self.accounts.validate(validation)
+    }
+
+    /// Account metas of the CPI, see `LockAccounts` for the order
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 14] {
+        self.accounts.account_metas()
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &UnlockInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &UnlockInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -0,0 +1,132 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{unverify::UnverifyInstructionData, Serialize},
+    pda,
+};
+
+/// Unverify a creator or a collection
+///
+/// ### Accounts:
+///   0. `[SIGNER]` Authority (account to be unverified)
+///   1. `[OPTIONAL]` Delegate Record
+///   2. `[WRITE]` Metadata
+///   3. `[OPTIONAL]` Collection Mint
+///   4. `[OPTIONAL, WRITE]` Collection Metadata
+///   5. `[]` System Program
+///   6. `[]` Sysvar Instructions
+///   7. `[]` MPL Token Metadata
+///
+/// Same accounts as Verify, without the collection master edition.
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct Unverify<'a> {
+    pub authority: &'a AccountInfo,
+    pub delegate_record: Option<&'a AccountInfo>,
+    pub metadata: &'a AccountInfo,
+    pub collection_mint: Option<&'a AccountInfo>,
+    pub collection_metadata: Option<&'a AccountInfo>,
+    pub system_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> Unverify<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &UnverifyInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &UnverifyInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+
+        // a collection that was burned has no metadata anymore, it isn't checked
+        check_mpl_owned(&[Some(self.metadata), self.delegate_record])?;
+
+        if let (Some(collection_mint), Some(collection_metadata)) =
+            (self.collection_mint, self.collection_metadata)
+        {
+            validation
+                .check_pda(|| pda::check_metadata(collection_metadata, collection_mint.key()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 8] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 8] {
+        [
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::optional(
+                self.delegate_record.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.collection_mint.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.collection_metadata.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &UnverifyInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &UnverifyInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
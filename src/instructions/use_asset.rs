@@ -0,0 +1,161 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Signer},
+    ProgramResult,
+};
+
+use super::{
+    invoke_signed, metas,
+    validation::{check_auth_rules_program, check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{use_asset::UseInstructionData, Serialize},
+    pda,
+    token_program::TokenProgram,
+};
+
+/// Use an asset, decrements the remaining uses of its metadata
+///
+/// ### Accounts:
+///   0. `[SIGNER, WRITE]` Authority (token owner or use delegate)
+///   1. `[OPTIONAL, WRITE]` Delegate Record
+///   2. `[OPTIONAL, WRITE]` Token Account
+///   3. `[]` Mint
+///   4. `[WRITE]` Metadata
+///   5. `[OPTIONAL]` Edition
+///   6. `[SIGNER, WRITE]` Payer
+///   7. `[]` System Program
+///   8. `[]` Sysvar Instructions
+///   9. `[OPTIONAL]` SPL Token Program
+///   10. `[OPTIONAL]` Auth Rules Program
+///   11. `[OPTIONAL]` Auth Rules Account
+///   12. `[]` MPL Token Metadata
+///
+/// Accounts being optional is very cursed but mimics the behaviour of the official lib.
+/// Accounts set to None get replaced by mpl program's account.
+pub struct UseAsset<'a> {
+    pub authority: &'a AccountInfo,
+    pub delegate_record: Option<&'a AccountInfo>,
+    pub token_account: Option<&'a AccountInfo>,
+    pub mint: &'a AccountInfo,
+    pub metadata: &'a AccountInfo,
+    pub edition: Option<&'a AccountInfo>,
+    pub payer: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub sysvar_instructions: &'a AccountInfo,
+    pub spl_token_program: Option<&'a AccountInfo>,
+    pub auth_rules_program: Option<&'a AccountInfo>,
+    pub auth_rules: Option<&'a AccountInfo>,
+    pub mpl_token_metadata: &'a AccountInfo,
+}
+
+impl<'a> UseAsset<'a> {
+    #[inline(always)]
+    pub fn invoke(
+        &self,
+        data: &UseInstructionData,
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        self.invoke_signed(data, &[], serialization_buffer)
+    }
+
+    pub fn invoke_signed(
+        &self,
+        data: &UseInstructionData,
+        signers: &[Signer],
+        serialization_buffer: &mut [u8],
+    ) -> ProgramResult {
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
+        check_auth_rules_program(self.auth_rules_program)?;
+
+        if let Some(spl_token_program) = self.spl_token_program {
+            let token_program = TokenProgram::check(spl_token_program)?;
+            token_program.check_owner(self.mint)?;
+            if let Some(token_account) = self.token_account {
+                token_program.check_owner(token_account)?;
+            }
+        }
+
+        check_mpl_owned(&[Some(self.metadata), self.edition, self.delegate_record])?;
+
+        validation.check_pda(|| pda::check_metadata(self.metadata, self.mint.key()))?;
+        if let Some(edition) = self.edition {
+            validation.check_pda(|| pda::check_master_edition(edition, self.mint.key()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 13] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 13] {
+        [
+            CpiAccount::writable_signer(self.authority),
+            CpiAccount::optional(
+                self.delegate_record.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.token_account.map(CpiAccount::writable),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mint),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.edition.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable_signer(self.payer),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::optional(
+                self.spl_token_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules_program.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.auth_rules.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &UseInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &UseInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
+    }
+}
@@ -0,0 +1,102 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::instructions::INSTRUCTIONS_ID, ProgramResult,
+};
+
+use super::CpiAccount;
+use crate::{
+    data::nft::check_owner, error::PnftError, MPL_TOKEN_AUTH_RULES_ID, MPL_TOKEN_METADATA_ID,
+    SYSTEM_PROGRAM_ID,
+};
+
+/// Options of the `validate` of the CPI structs.
+///
+/// Validation is opt-in, token metadata checks everything again anyway. It is meant to fail early,
+/// with an error of this crate that says what is wrong instead of an obscure one from token metadata.
+#[derive(Clone, Copy, Default)]
+pub struct Validation<'a> {
+    /// PDAs of the calling program that sign through `invoke_signed`.
+    /// They aren't signers of the transaction, so they would fail the signer check
+    pub pda_signers: &'a [&'a Pubkey],
+    /// Also derive the token metadata PDAs (metadata, editions, token records) and compare them
+    /// to the accounts. Each one costs a `find_program_address`
+    pub check_pdas: bool,
+}
+
+impl Validation<'_> {
+    /// Signer and writable flags of every account, as the CPI passes them
+    pub(crate) fn check_flags(&self, accounts: &[CpiAccount]) -> ProgramResult {
+        for account in accounts {
+            if account.signer
+                && !account.info.is_signer()
+                && !self.pda_signers.contains(&account.info.key())
+            {
+                return Err(PnftError::MissingSigner.into());
+            }
+
+            if account.writable && !account.info.is_writable() {
+                return Err(PnftError::NotWritable.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `check` is a `pda::check_*`, only called when PDAs are checked
+    pub(crate) fn check_pda(
+        &self,
+        check: impl FnOnce() -> Result<u8, ProgramError>,
+    ) -> ProgramResult {
+        if self.check_pdas {
+            check()?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn check_program(account: &AccountInfo, id: &Pubkey) -> ProgramResult {
+    if account.key() != id {
+        return Err(PnftError::InvalidProgramId.into());
+    }
+
+    Ok(())
+}
+
+/// System program, sysvar instructions and token metadata, that most instructions take
+pub(crate) fn check_programs(
+    system_program: &AccountInfo,
+    sysvar_instructions: &AccountInfo,
+    mpl_token_metadata: &AccountInfo,
+) -> ProgramResult {
+    check_program(system_program, &SYSTEM_PROGRAM_ID)?;
+    check_program(sysvar_instructions, &INSTRUCTIONS_ID)?;
+    check_program(mpl_token_metadata, &MPL_TOKEN_METADATA_ID)
+}
+
+pub(crate) fn check_auth_rules_program(auth_rules_program: Option<&AccountInfo>) -> ProgramResult {
+    match auth_rules_program {
+        Some(auth_rules_program) => check_program(auth_rules_program, &MPL_TOKEN_AUTH_RULES_ID),
+        None => Ok(()),
+    }
+}
+
+/// Accounts of token metadata (metadata, editions, records) that have to exist already
+pub(crate) fn check_mpl_owned(accounts: &[Option<&AccountInfo>]) -> ProgramResult {
+    for account in accounts.iter().flatten() {
+        check_owner(account)?;
+    }
+
+    Ok(())
+}
+
+/// Same as `check_mpl_owned`, for accounts that token metadata may create during the instruction
+pub(crate) fn check_mpl_owned_if_created(accounts: &[Option<&AccountInfo>]) -> ProgramResult {
+    for account in accounts.iter().flatten() {
+        if !account.data_is_empty() {
+            check_owner(account)?;
+        }
+    }
+
+    Ok(())
+}
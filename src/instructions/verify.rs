@@ -1,13 +1,20 @@
 use pinocchio::{
     account_info::AccountInfo,
-    cpi::invoke_signed,
-    instruction::{AccountMeta, Instruction, Signer},
+    instruction::{AccountMeta, Signer},
     ProgramResult,
 };
 
-use crate::data::{verify::VerifyInstructionData, Serialize};
+use super::{
+    invoke_signed, metas,
+    validation::{check_mpl_owned, check_programs},
+    CpiAccount, Validation,
+};
+use crate::{
+    data::{verify::VerifyInstructionData, Serialize},
+    pda,
+};
 
-/// Transfer an asset
+/// Verify a creator or a collection
 ///
 /// ### Accounts:
 ///   0. `[SIGNER]` Authority (account to be verified)
@@ -34,7 +41,7 @@ pub struct Verify<'a> {
     pub mpl_token_metadata: &'a AccountInfo,
 }
 
-impl Verify<'_> {
+impl<'a> Verify<'a> {
     #[inline(always)]
     pub fn invoke(
         &self,
@@ -50,55 +57,90 @@ impl Verify<'_> {
         signers: &[Signer],
         serialization_buffer: &mut [u8],
     ) -> ProgramResult {
-        // account metadata
-        let account_metas: &[AccountMeta] = &[
-            AccountMeta::readonly_signer(self.authority.key()),
-            match self.delegate_record {
-                Some(delegate) => AccountMeta::readonly(delegate.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::writable(self.metadata.key()),
-            match self.collection_mint {
-                Some(mint) => AccountMeta::readonly(mint.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.collection_metadata {
-                Some(meta) => AccountMeta::writable(meta.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            match self.collection_master_edition {
-                Some(edition) => AccountMeta::readonly(edition.key()),
-                None => AccountMeta::readonly(self.mpl_token_metadata.key()),
-            },
-            AccountMeta::readonly(self.system_program.key()),
-            AccountMeta::readonly(self.sysvar_instructions.key()),
-            AccountMeta::readonly(self.mpl_token_metadata.key()),
-        ];
+        let len = data.try_serialize_to(serialization_buffer)?;
+
+        invoke_signed(&self.accounts(), &serialization_buffer[..len], signers)
+    }
+
+    /// Checks the accounts against the table above, see `Validation`
+    pub fn validate(&self, validation: &Validation) -> ProgramResult {
+        validation.check_flags(&self.accounts())?;
+        check_programs(
+            self.system_program,
+            self.sysvar_instructions,
+            self.mpl_token_metadata,
+        )?;
 
-        let len = data.serialize_to(serialization_buffer);
-        let data = &serialization_buffer[..len];
+        check_mpl_owned(&[
+            Some(self.metadata),
+            self.collection_metadata,
+            self.collection_master_edition,
+            self.delegate_record,
+        ])?;
 
-        let instruction = Instruction {
-            program_id: &crate::MPL_TOKEN_METADATA_ID,
-            accounts: &account_metas,
-            data,
-        };
+        if let Some(collection_mint) = self.collection_mint {
+            if let Some(collection_metadata) = self.collection_metadata {
+                validation.check_pda(|| {
+                    pda::check_metadata(collection_metadata, collection_mint.key())
+                })?;
+            }
+            if let Some(collection_master_edition) = self.collection_master_edition {
+                validation.check_pda(|| {
+                    pda::check_master_edition(collection_master_edition, collection_mint.key())
+                })?;
+            }
+        }
 
-        invoke_signed(
-            &instruction,
-            &[
-                self.authority,
-                self.delegate_record.unwrap_or(self.mpl_token_metadata),
-                self.metadata,
-                self.collection_mint.unwrap_or(self.mpl_token_metadata),
-                self.collection_metadata.unwrap_or(self.mpl_token_metadata),
-                self.collection_master_edition
-                    .unwrap_or(self.mpl_token_metadata),
-                self.system_program,
-                self.sysvar_instructions,
+        Ok(())
+    }
+
+    /// Account metas of the CPI, in the order of the table above
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 9] {
+        metas(&self.accounts())
+    }
+
+    /// In the order of the table above
+    fn accounts(&self) -> [CpiAccount<'a>; 9] {
+        [
+            CpiAccount::readonly_signer(self.authority),
+            CpiAccount::optional(
+                self.delegate_record.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::writable(self.metadata),
+            CpiAccount::optional(
+                self.collection_mint.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::optional(
+                self.collection_metadata.map(CpiAccount::writable),
                 self.mpl_token_metadata,
-            ],
-            signers,
-        )
+            ),
+            CpiAccount::optional(
+                self.collection_master_edition.map(CpiAccount::readonly),
+                self.mpl_token_metadata,
+            ),
+            CpiAccount::readonly(self.system_program),
+            CpiAccount::readonly(self.sysvar_instructions),
+            CpiAccount::readonly(self.mpl_token_metadata),
+        ]
+    }
+
+    /// Same as `invoke`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn invoke_alloc(&self, data: &VerifyInstructionData) -> ProgramResult {
+        self.invoke_signed_alloc(data, &[])
+    }
+
+    /// Same as `invoke_signed`, but allocates a buffer of the exact size
+    #[cfg(feature = "alloc")]
+    pub fn invoke_signed_alloc(
+        &self,
+        data: &VerifyInstructionData,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let mut serialization_buffer = alloc::vec![0; data.serialized_len()];
+        self.invoke_signed(data, signers, &mut serialization_buffer)
     }
 }
@@ -0,0 +1,191 @@
+use core::ops::Deref;
+
+use pinocchio::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::instructions::{
+        Instructions as InstructionsSysvar, IntrospectedAccountMeta, IntrospectedInstruction,
+    },
+};
+
+use crate::{decode_instruction, TokenMetadataInstruction, MPL_TOKEN_METADATA_ID};
+
+/// Where to look for instructions, relative to the one currently executing
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Before,
+    After,
+    /// Every instruction except the current one
+    Anywhere,
+}
+
+/// A token metadata instruction of the transaction, read from the instructions sysvar
+///
+/// The sysvar only has the top level instructions, anything done through CPI can't be seen here.
+pub struct TokenMetadataCall<'a> {
+    /// Index of the instruction in the transaction
+    pub index: u16,
+    pub instruction: TokenMetadataInstruction<'a>,
+    introspected: IntrospectedInstruction<'a>,
+}
+
+impl<'a> TokenMetadataCall<'a> {
+    /// Account at `index`, in the order the token metadata program reads them
+    pub fn account_meta(&self, index: usize) -> Option<&IntrospectedAccountMeta> {
+        self.introspected.get_account_meta_at(index).ok()
+    }
+
+    pub fn account(&self, index: usize) -> Option<&Pubkey> {
+        self.account_meta(index).map(|meta| &meta.key)
+    }
+
+    /// The mint of the asset the instruction is about, the new edition for Print.
+    /// None for Verify and Unverify, the asset is only given by its metadata there
+    pub fn mint(&self) -> Option<&Pubkey> {
+        match self.instruction {
+            TokenMetadataInstruction::CreateMasterEditionV3(_)
+            | TokenMetadataInstruction::CreateMetadataAccountV3(_) => self.account(1),
+            TokenMetadataInstruction::Create(_) | TokenMetadataInstruction::Print(_) => {
+                self.account(2)
+            }
+            TokenMetadataInstruction::Lock(_)
+            | TokenMetadataInstruction::Unlock(_)
+            | TokenMetadataInstruction::Update(_)
+            | TokenMetadataInstruction::Use(_) => self.account(3),
+            TokenMetadataInstruction::Burn(_)
+            | TokenMetadataInstruction::Migrate(_)
+            | TokenMetadataInstruction::Transfer(_) => self.account(4),
+            TokenMetadataInstruction::Mint(_)
+            | TokenMetadataInstruction::Delegate(_)
+            | TokenMetadataInstruction::Revoke(_) => self.account(5),
+            TokenMetadataInstruction::Verify(_)
+            | TokenMetadataInstruction::Unverify(_)
+            | TokenMetadataInstruction::Unknown(_) => None,
+        }
+    }
+
+    /// The delegate being set or revoked, for Delegate and Revoke
+    pub fn delegate(&self) -> Option<&Pubkey> {
+        match self.instruction {
+            TokenMetadataInstruction::Delegate(_) | TokenMetadataInstruction::Revoke(_) => {
+                self.account(1)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Iterates over the token metadata instructions of the transaction, in order,
+/// decoding them as it goes. Instructions of other programs are skipped.
+///
+/// A token metadata instruction whose data doesn't decode is returned as an
+/// `InvalidInstructionData` error rather than skipped, so a caller looking for a transfer
+/// (for example) can't miss one this crate fails to read.
+/// Discriminators this crate doesn't know are still returned, as `Unknown`
+pub struct TokenMetadataCalls<'a, T: Deref<Target = [u8]>> {
+    sysvar: &'a InstructionsSysvar<T>,
+    index: u16,
+    end: u16,
+    /// Skipped when looking anywhere
+    current: u16,
+}
+
+impl<'a, T: Deref<Target = [u8]>> TokenMetadataCalls<'a, T> {
+    pub fn new(sysvar: &'a InstructionsSysvar<T>, position: Position) -> Self {
+        let current = sysvar.load_current_index();
+
+        let (index, end) = match position {
+            Position::Before => (0, current),
+            Position::After => (current.saturating_add(1), sysvar.num_instructions()),
+            Position::Anywhere => (0, sysvar.num_instructions()),
+        };
+
+        Self {
+            sysvar,
+            index,
+            end,
+            current,
+        }
+    }
+
+    fn read(&self, index: u16) -> Result<Option<TokenMetadataCall<'a>>, ProgramError> {
+        let instruction = self.sysvar.load_instruction_at(index as usize)?;
+
+        if instruction.get_program_id() != &MPL_TOKEN_METADATA_ID {
+            return Ok(None);
+        }
+
+        // the introspected instruction only lends its data for as long as it lives,
+        // but the data is in the sysvar, which lives for 'a
+        let data = unsafe { &*(instruction.get_instruction_data() as *const [u8]) };
+
+        Ok(Some(TokenMetadataCall {
+            index,
+            instruction: decode_instruction(data)?,
+            introspected: instruction,
+        }))
+    }
+}
+
+impl<'a, T: Deref<Target = [u8]>> Iterator for TokenMetadataCalls<'a, T> {
+    type Item = Result<TokenMetadataCall<'a>, ProgramError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.end {
+            let index = self.index;
+            self.index += 1;
+
+            if index == self.current {
+                continue;
+            }
+
+            match self.read(index) {
+                Ok(Some(call)) => return Some(Ok(call)),
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+/// First token metadata Transfer of `mint`, if there is one
+pub fn find_transfer<'a, T: Deref<Target = [u8]>>(
+    sysvar: &'a InstructionsSysvar<T>,
+    mint: &Pubkey,
+    position: Position,
+) -> Result<Option<TokenMetadataCall<'a>>, ProgramError> {
+    for call in TokenMetadataCalls::new(sysvar, position) {
+        let call = call?;
+
+        if matches!(call.instruction, TokenMetadataInstruction::Transfer(_))
+            && call.mint() == Some(mint)
+        {
+            return Ok(Some(call));
+        }
+    }
+
+    Ok(None)
+}
+
+/// First token metadata Delegate of `mint` to `delegate`, if there is one
+pub fn find_delegate<'a, T: Deref<Target = [u8]>>(
+    sysvar: &'a InstructionsSysvar<T>,
+    mint: &Pubkey,
+    delegate: &Pubkey,
+    position: Position,
+) -> Result<Option<TokenMetadataCall<'a>>, ProgramError> {
+    for call in TokenMetadataCalls::new(sysvar, position) {
+        let call = call?;
+
+        if matches!(call.instruction, TokenMetadataInstruction::Delegate(_))
+            && call.mint() == Some(mint)
+            && call.delegate() == Some(delegate)
+        {
+            return Ok(Some(call));
+        }
+    }
+
+    Ok(None)
+}
@@ -1,32 +1,90 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use pinocchio::pubkey::Pubkey;
 use pinocchio_pubkey::pubkey;
 
 pub mod data;
+pub mod decode;
+pub mod error;
 pub mod instructions;
+pub mod introspection;
+pub mod pda;
+pub mod token_program;
+
+pub use decode::{decode_instruction, TokenMetadataInstruction};
 
 /// For internal use, to get the discriminant of the instruction
+#[derive(Clone, Copy)]
 #[repr(u8)]
 pub(crate) enum Instructions {
+    CreateMasterEditionV3 = 17,
+    CreateMetadataAccountV3 = 33,
     Burn = 41,
     Create = 42,
     Mint = 43,
+    Delegate = 44,
+    Revoke = 45,
+    Lock = 46,
+    Unlock = 47,
+    Migrate = 48,
     Transfer = 49,
-    // Update = 50,
+    Update = 50,
+    Use = 51,
     Verify = 52,
+    Unverify = 53,
+    Print = 55,
 }
 
 impl Instructions {
     pub fn to_u8(self) -> u8 {
         match self {
+            Self::CreateMasterEditionV3 => 17,
+            Self::CreateMetadataAccountV3 => 33,
             Self::Burn => 41,
             Self::Create => 42,
             Self::Mint => 43,
+            Self::Delegate => 44,
+            Self::Revoke => 45,
+            Self::Lock => 46,
+            Self::Unlock => 47,
+            Self::Migrate => 48,
             Self::Transfer => 49,
+            Self::Update => 50,
+            Self::Use => 51,
             Self::Verify => 52,
+            Self::Unverify => 53,
+            Self::Print => 55,
+        }
+    }
+
+    pub fn from_u8(discriminator: u8) -> Option<Self> {
+        match discriminator {
+            17 => Some(Self::CreateMasterEditionV3),
+            33 => Some(Self::CreateMetadataAccountV3),
+            41 => Some(Self::Burn),
+            42 => Some(Self::Create),
+            43 => Some(Self::Mint),
+            44 => Some(Self::Delegate),
+            45 => Some(Self::Revoke),
+            46 => Some(Self::Lock),
+            47 => Some(Self::Unlock),
+            48 => Some(Self::Migrate),
+            49 => Some(Self::Transfer),
+            50 => Some(Self::Update),
+            51 => Some(Self::Use),
+            52 => Some(Self::Verify),
+            53 => Some(Self::Unverify),
+            55 => Some(Self::Print),
+            _ => None,
         }
     }
 }
 
 pub const MPL_TOKEN_METADATA_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const MPL_TOKEN_AUTH_RULES_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
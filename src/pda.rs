@@ -0,0 +1,564 @@
+//! Derivation of the token metadata PDAs.
+//!
+//! Every account has a `find_*` (searches for the bump, expensive), a `create_*_with_bump`
+//! (cheap, when the bump is already known) and a `check_*` that makes sure the account that
+//! was passed in is the expected PDA, returning its bump.
+//!
+//! All three go through the runtime syscalls, so they panic off chain (in tests, for example).
+
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{create_program_address, find_program_address, Pubkey},
+};
+
+use crate::{data::nft::EDITION_MARKER_BIT_SIZE, error::PnftError, MPL_TOKEN_METADATA_ID};
+
+pub const PREFIX: &[u8] = b"metadata";
+pub const EDITION: &[u8] = b"edition";
+pub const MARKER: &[u8] = b"marker";
+pub const TOKEN_RECORD: &[u8] = b"token_record";
+pub const COLLECTION_AUTHORITY: &[u8] = b"collection_authority";
+pub const USER: &[u8] = b"user";
+pub const ESCROW: &[u8] = b"escrow";
+
+/// Role of a metadata delegate, used as a seed of its delegate record
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MetadataDelegateRole {
+    AuthorityItem,
+    Collection,
+    Use,
+    Data,
+    ProgrammableConfig,
+    DataItem,
+    CollectionItem,
+    ProgrammableConfigItem,
+}
+
+impl MetadataDelegateRole {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            Self::AuthorityItem => b"authority_item_delegate",
+            Self::Collection => b"collection_delegate",
+            Self::Use => b"use_delegate",
+            Self::Data => b"data_delegate",
+            Self::ProgrammableConfig => b"programmable_config_delegate",
+            Self::DataItem => b"data_item_delegate",
+            Self::CollectionItem => b"collection_item_delegate",
+            // not a typo, the full name doesn't fit in a seed
+            Self::ProgrammableConfigItem => b"prog_config_item_delegate",
+        }
+    }
+}
+
+/// Role of a holder delegate, used as a seed of its delegate record
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HolderDelegateRole {
+    PrintDelegate,
+}
+
+impl HolderDelegateRole {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            Self::PrintDelegate => b"print_delegate",
+        }
+    }
+}
+
+/// Who is allowed to use a token owned escrow, used as a seed of the escrow
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EscrowAuthority<'a> {
+    TokenOwner,
+    Creator(&'a Pubkey),
+}
+
+#[inline(always)]
+fn check(account: &AccountInfo, (expected, bump): (Pubkey, u8)) -> Result<u8, ProgramError> {
+    if account.key() != &expected {
+        return Err(PnftError::InvalidPda.into());
+    }
+
+    Ok(bump)
+}
+
+// Metadata: ["metadata", program id, mint]
+
+pub fn find_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[PREFIX, MPL_TOKEN_METADATA_ID.as_ref(), mint],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_metadata_with_bump(mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[PREFIX, MPL_TOKEN_METADATA_ID.as_ref(), mint, &[bump]],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_metadata(account: &AccountInfo, mint: &Pubkey) -> Result<u8, ProgramError> {
+    check(account, find_metadata(mint))
+}
+
+// Master edition (and print edition): ["metadata", program id, mint, "edition"]
+
+pub fn find_master_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[PREFIX, MPL_TOKEN_METADATA_ID.as_ref(), mint, EDITION],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_master_edition_with_bump(mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            EDITION,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_master_edition(account: &AccountInfo, mint: &Pubkey) -> Result<u8, ProgramError> {
+    check(account, find_master_edition(mint))
+}
+
+// Edition marker: ["metadata", program id, mint, "edition", (edition / 248) as a decimal string]
+
+/// Writes the marker number of an edition as a decimal string, since that's what is used as the seed
+fn edition_marker_seed(edition: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut marker = edition / EDITION_MARKER_BIT_SIZE;
+    let mut start = buffer.len();
+
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (marker % 10) as u8;
+        marker /= 10;
+
+        if marker == 0 {
+            break;
+        }
+    }
+
+    &buffer[start..]
+}
+
+/// `mint` is the mint of the master edition, not of the print
+pub fn find_edition_marker(mint: &Pubkey, edition: u64) -> (Pubkey, u8) {
+    let mut buffer = [0; 20];
+    let marker = edition_marker_seed(edition, &mut buffer);
+
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            EDITION,
+            marker,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_edition_marker_with_bump(
+    mint: &Pubkey,
+    edition: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    let mut buffer = [0; 20];
+    let marker = edition_marker_seed(edition, &mut buffer);
+
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            EDITION,
+            marker,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_edition_marker(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    edition: u64,
+) -> Result<u8, ProgramError> {
+    check(account, find_edition_marker(mint, edition))
+}
+
+// Edition marker V2: ["metadata", program id, mint, "edition", "marker"]
+
+/// `mint` is the mint of the master edition, not of the print
+pub fn find_edition_marker_v2(mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            EDITION,
+            MARKER,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_edition_marker_v2_with_bump(mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            EDITION,
+            MARKER,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_edition_marker_v2(account: &AccountInfo, mint: &Pubkey) -> Result<u8, ProgramError> {
+    check(account, find_edition_marker_v2(mint))
+}
+
+// Token record: ["metadata", program id, mint, "token_record", token account]
+
+pub fn find_token_record(mint: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            TOKEN_RECORD,
+            token,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_token_record_with_bump(
+    mint: &Pubkey,
+    token: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            TOKEN_RECORD,
+            token,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_token_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    token: &Pubkey,
+) -> Result<u8, ProgramError> {
+    check(account, find_token_record(mint, token))
+}
+
+// Metadata delegate record: ["metadata", program id, mint, role, update authority, delegate]
+
+pub fn find_metadata_delegate_record(
+    mint: &Pubkey,
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            role.seed(),
+            update_authority,
+            delegate,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_metadata_delegate_record_with_bump(
+    mint: &Pubkey,
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            role.seed(),
+            update_authority,
+            delegate,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_metadata_delegate_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    role: MetadataDelegateRole,
+    update_authority: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<u8, ProgramError> {
+    check(
+        account,
+        find_metadata_delegate_record(mint, role, update_authority, delegate),
+    )
+}
+
+// Holder delegate record: ["metadata", program id, mint, role, owner, delegate]
+
+pub fn find_holder_delegate_record(
+    mint: &Pubkey,
+    role: HolderDelegateRole,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            role.seed(),
+            owner,
+            delegate,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_holder_delegate_record_with_bump(
+    mint: &Pubkey,
+    role: HolderDelegateRole,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            role.seed(),
+            owner,
+            delegate,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_holder_delegate_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    role: HolderDelegateRole,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<u8, ProgramError> {
+    check(
+        account,
+        find_holder_delegate_record(mint, role, owner, delegate),
+    )
+}
+
+// Collection authority record: ["metadata", program id, mint, "collection_authority", authority]
+
+pub fn find_collection_authority_record(
+    mint: &Pubkey,
+    collection_authority: &Pubkey,
+) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            COLLECTION_AUTHORITY,
+            collection_authority,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_collection_authority_record_with_bump(
+    mint: &Pubkey,
+    collection_authority: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            COLLECTION_AUTHORITY,
+            collection_authority,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_collection_authority_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    collection_authority: &Pubkey,
+) -> Result<u8, ProgramError> {
+    check(
+        account,
+        find_collection_authority_record(mint, collection_authority),
+    )
+}
+
+// Use authority record: ["metadata", program id, mint, "user", use authority]
+
+pub fn find_use_authority_record(mint: &Pubkey, use_authority: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            USER,
+            use_authority,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_use_authority_record_with_bump(
+    mint: &Pubkey,
+    use_authority: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            USER,
+            use_authority,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_use_authority_record(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    use_authority: &Pubkey,
+) -> Result<u8, ProgramError> {
+    check(account, find_use_authority_record(mint, use_authority))
+}
+
+// Token owned escrow: ["metadata", program id, mint, authority, "escrow"]
+// where authority is [0] for the token owner and [1, creator..] for a creator
+
+/// Writes the escrow authority seed, since it is a single seed for both variants
+fn escrow_authority_seed<'b>(authority: EscrowAuthority, buffer: &'b mut [u8; 33]) -> &'b [u8] {
+    match authority {
+        EscrowAuthority::TokenOwner => {
+            buffer[0] = 0;
+            &buffer[..1]
+        }
+        EscrowAuthority::Creator(creator) => {
+            buffer[0] = 1;
+            buffer[1..].copy_from_slice(creator);
+            &buffer[..]
+        }
+    }
+}
+
+pub fn find_escrow(mint: &Pubkey, authority: EscrowAuthority) -> (Pubkey, u8) {
+    let mut buffer = [0; 33];
+    let authority = escrow_authority_seed(authority, &mut buffer);
+
+    find_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            authority,
+            ESCROW,
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn create_escrow_with_bump(
+    mint: &Pubkey,
+    authority: EscrowAuthority,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    let mut buffer = [0; 33];
+    let authority = escrow_authority_seed(authority, &mut buffer);
+
+    create_program_address(
+        &[
+            PREFIX,
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint,
+            authority,
+            ESCROW,
+            &[bump],
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+}
+
+pub fn check_escrow(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    authority: EscrowAuthority,
+) -> Result<u8, ProgramError> {
+    check(account, find_escrow(mint, authority))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn edition_marker_seed_is_the_decimal_marker_number() {
+        for edition in [0, 247, 248, 1_000, u64::MAX] {
+            let mut buffer = [0; 20];
+            let seed = edition_marker_seed(edition, &mut buffer);
+
+            assert_eq!(seed, (edition / 248).to_string().as_bytes());
+        }
+    }
+
+    #[test]
+    fn escrow_authority_seed_of_both_authorities() {
+        let mut buffer = [0; 33];
+        assert_eq!(
+            escrow_authority_seed(EscrowAuthority::TokenOwner, &mut buffer),
+            [0]
+        );
+
+        let creator = [7; 32];
+        let mut buffer = [0; 33];
+        let seed = escrow_authority_seed(EscrowAuthority::Creator(&creator), &mut buffer);
+        assert_eq!(seed[0], 1);
+        assert_eq!(seed[1..], creator);
+    }
+}
@@ -0,0 +1,74 @@
+//! SPL Token and Token-2022 (Token Extensions).
+//!
+//! Token metadata accepts either program for the token accounts of an asset, the one that is used
+//! is the one that is passed to the CPI. The `check_token_program` of the CPI structs makes sure
+//! it is one of the two and that the mint and token accounts belong to it.
+
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_pubkey::pubkey;
+
+use crate::error::PnftError;
+
+pub const SPL_TOKEN_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+/// Same program for both token programs
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xCW3WCHLy3fcBjPQ");
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TokenProgram {
+    SplToken,
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn id(&self) -> &'static Pubkey {
+        match self {
+            Self::SplToken => &SPL_TOKEN_ID,
+            Self::Token2022 => &TOKEN_2022_ID,
+        }
+    }
+
+    pub fn from_id(id: &Pubkey) -> Option<Self> {
+        if id == &SPL_TOKEN_ID {
+            Some(Self::SplToken)
+        } else if id == &TOKEN_2022_ID {
+            Some(Self::Token2022)
+        } else {
+            None
+        }
+    }
+
+    /// Which program the account is, if it is one of the two
+    pub fn check(account: &AccountInfo) -> Result<Self, ProgramError> {
+        Self::from_id(account.key()).ok_or(PnftError::InvalidTokenProgram.into())
+    }
+
+    /// Which program owns the mint or token account, for instructions that don't take the
+    /// token program and accept either
+    pub fn from_owner(account: &AccountInfo) -> Result<Self, ProgramError> {
+        [Self::SplToken, Self::Token2022]
+            .into_iter()
+            .find(|program| account.is_owned_by(program.id()))
+            .ok_or(PnftError::TokenProgramMismatch.into())
+    }
+
+    /// Makes sure the mint or token account belongs to this program
+    pub fn check_owner(&self, account: &AccountInfo) -> Result<(), ProgramError> {
+        if !account.is_owned_by(self.id()) {
+            return Err(PnftError::TokenProgramMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Same as `check_owner`, but accounts that don't exist yet are fine,
+    /// token metadata creates them during the instruction
+    pub(crate) fn check_owner_if_created(&self, account: &AccountInfo) -> Result<(), ProgramError> {
+        if account.data_is_empty() {
+            return Ok(());
+        }
+
+        self.check_owner(account)
+    }
+}
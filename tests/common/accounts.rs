@@ -0,0 +1,88 @@
+//! Account infos laid out like the runtime gives them to the entrypoint
+
+use std::mem::MaybeUninit;
+
+use pinocchio::{
+    account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+    entrypoint::{deserialize, NON_DUP_MARKER},
+    pubkey::Pubkey,
+};
+
+/// Account header, the space for the data to grow and the rent epoch. The data itself comes after
+const ACCOUNT_LEN: usize = 88 + MAX_PERMITTED_DATA_INCREASE + 8;
+
+const MAX_ACCOUNTS: usize = 32;
+
+/// What the tests care about in an account, its data is zeroed
+#[derive(Clone, Copy, Default)]
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub data_len: usize,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+pub struct Accounts {
+    /// The account infos point into it, u64 for the alignment
+    _input: Vec<u64>,
+    infos: Vec<AccountInfo>,
+}
+
+impl Accounts {
+    /// Every account has a different key, `[i + 1; 32]`, no owner and no data
+    pub fn new(count: usize) -> Self {
+        let accounts: Vec<_> = (0..count)
+            .map(|i| TestAccount {
+                key: [i as u8 + 1; 32],
+                ..Default::default()
+            })
+            .collect();
+
+        Self::build(&accounts)
+    }
+
+    pub fn build(accounts: &[TestAccount]) -> Self {
+        assert!(accounts.len() <= MAX_ACCOUNTS);
+
+        let mut bytes = (accounts.len() as u64).to_le_bytes().to_vec();
+        for account in accounts {
+            let mut header = vec![0; ACCOUNT_LEN];
+            header[0] = NON_DUP_MARKER;
+            header[1] = account.signer as u8;
+            header[2] = account.writable as u8;
+            header[8..40].copy_from_slice(&account.key);
+            header[40..72].copy_from_slice(&account.owner);
+            header[80..88].copy_from_slice(&(account.data_len as u64).to_le_bytes());
+            bytes.extend(header);
+            bytes.extend(vec![0; account.data_len.next_multiple_of(8)]);
+        }
+        // no instruction data, then the program id
+        bytes.extend([0; 8 + 32]);
+
+        let mut input = vec![0u64; bytes.len().div_ceil(8)];
+        for (word, chunk) in input.iter_mut().zip(bytes.chunks(8)) {
+            let mut le = [0; 8];
+            le[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_le_bytes(le);
+        }
+
+        let mut infos = [const { MaybeUninit::<AccountInfo>::uninit() }; MAX_ACCOUNTS];
+        let (_, read, _) = unsafe { deserialize(input.as_mut_ptr() as *mut u8, &mut infos) };
+        assert_eq!(read, accounts.len());
+
+        let infos = infos[..read]
+            .iter()
+            .map(|info| unsafe { info.assume_init() })
+            .collect();
+
+        Self {
+            _input: input,
+            infos,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> &AccountInfo {
+        &self.infos[index]
+    }
+}
@@ -0,0 +1,492 @@
+#![allow(dead_code)]
+
+pub mod accounts;
+pub mod mpl;
+
+use std::collections::HashMap;
+
+use pnft_pinocchio::data::{
+    authorization::{AuthorizationData, Payload, PayloadKey, PayloadType},
+    create::{
+        AssetData, Collection, CollectionDetails, PrintSupply, TokenStandard, UseMethod, Uses,
+    },
+    create_metadata_account_v3::DataV2,
+    nft::{Creator, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH},
+    update::{Data, Toggle},
+    Deserialize, Serialize,
+};
+
+/// How many random values each test goes through
+pub const ITERATIONS: usize = 500;
+
+/// Small xorshift, so the tests don't need a rand dependency and failures are reproducible
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Biased towards the edges, that's where encodings break
+    pub fn number(&mut self) -> u64 {
+        match self.below(4) {
+            0 => 0,
+            1 => u64::MAX,
+            _ => self.u64(),
+        }
+    }
+
+    pub fn below(&mut self, max: usize) -> usize {
+        (self.u64() % max as u64) as usize
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        self.u64() as u8
+    }
+
+    pub fn u16(&mut self) -> u16 {
+        self.u64() as u16
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u64() & 1 == 1
+    }
+
+    pub fn pubkey(&mut self) -> [u8; 32] {
+        core::array::from_fn(|_| self.u8())
+    }
+
+    pub fn bytes(&mut self, max_len: usize) -> Vec<u8> {
+        let len = self.below(max_len + 1);
+        (0..len).map(|_| self.u8()).collect()
+    }
+
+    /// Up to `max_len` bytes, with some multi byte characters thrown in
+    pub fn string(&mut self, max_len: usize) -> String {
+        let mut string = String::new();
+        let target = self.below(max_len + 1);
+
+        while string.len() < target {
+            let c = match self.below(8) {
+                0 if string.len() + 2 <= target => 'é',
+                _ => (b'a' + self.below(26) as u8) as char,
+            };
+            string.push(c);
+        }
+
+        string
+    }
+
+    pub fn option<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        self.bool().then(|| f(self))
+    }
+}
+
+// random reference values
+
+pub fn creators(rng: &mut Rng) -> Option<Vec<mpl::Creator>> {
+    rng.option(|rng| {
+        (0..rng.below(MAX_CREATOR_LIMIT + 1))
+            .map(|_| mpl::Creator {
+                address: rng.pubkey(),
+                verified: rng.bool(),
+                share: rng.u8(),
+            })
+            .collect()
+    })
+}
+
+pub fn collection(rng: &mut Rng) -> mpl::Collection {
+    mpl::Collection {
+        verified: rng.bool(),
+        key: rng.pubkey(),
+    }
+}
+
+pub fn use_method(rng: &mut Rng) -> mpl::UseMethod {
+    [
+        mpl::UseMethod::Burn,
+        mpl::UseMethod::Multiple,
+        mpl::UseMethod::Single,
+    ][rng.below(3)]
+}
+
+pub fn uses(rng: &mut Rng) -> mpl::Uses {
+    mpl::Uses {
+        use_method: use_method(rng),
+        remaining: rng.number(),
+        total: rng.number(),
+    }
+}
+
+pub fn collection_details(rng: &mut Rng) -> mpl::CollectionDetails {
+    if rng.bool() {
+        mpl::CollectionDetails::V1 { size: rng.number() }
+    } else {
+        mpl::CollectionDetails::V2 {
+            padding: core::array::from_fn(|_| rng.u8()),
+        }
+    }
+}
+
+pub const TOKEN_STANDARDS: [mpl::TokenStandard; 6] = [
+    mpl::TokenStandard::NonFungible,
+    mpl::TokenStandard::FungibleAsset,
+    mpl::TokenStandard::Fungible,
+    mpl::TokenStandard::NonFungibleEdition,
+    mpl::TokenStandard::ProgrammableNonFungible,
+    mpl::TokenStandard::ProgrammableNonFungibleEdition,
+];
+
+pub fn token_standard(rng: &mut Rng) -> mpl::TokenStandard {
+    TOKEN_STANDARDS[rng.below(TOKEN_STANDARDS.len())]
+}
+
+pub fn print_supply(rng: &mut Rng) -> mpl::PrintSupply {
+    match rng.below(3) {
+        0 => mpl::PrintSupply::Zero,
+        1 => mpl::PrintSupply::Limited(rng.number()),
+        _ => mpl::PrintSupply::Unlimited,
+    }
+}
+
+pub fn asset_data(rng: &mut Rng) -> mpl::AssetData {
+    mpl::AssetData {
+        name: rng.string(MAX_NAME_LENGTH),
+        symbol: rng.string(MAX_SYMBOL_LENGTH),
+        uri: rng.string(MAX_URI_LENGTH),
+        seller_fee_basis_points: rng.u16(),
+        creators: creators(rng),
+        primary_sale_happened: rng.bool(),
+        is_mutable: rng.bool(),
+        token_standard: token_standard(rng),
+        collection: rng.option(collection),
+        uses: rng.option(uses),
+        collection_details: rng.option(collection_details),
+        rule_set: rng.option(Rng::pubkey),
+    }
+}
+
+pub fn data(rng: &mut Rng) -> mpl::Data {
+    mpl::Data {
+        name: rng.string(MAX_NAME_LENGTH),
+        symbol: rng.string(MAX_SYMBOL_LENGTH),
+        uri: rng.string(MAX_URI_LENGTH),
+        seller_fee_basis_points: rng.u16(),
+        creators: creators(rng),
+    }
+}
+
+pub fn data_v2(rng: &mut Rng) -> mpl::DataV2 {
+    mpl::DataV2 {
+        name: rng.string(MAX_NAME_LENGTH),
+        symbol: rng.string(MAX_SYMBOL_LENGTH),
+        uri: rng.string(MAX_URI_LENGTH),
+        seller_fee_basis_points: rng.u16(),
+        creators: creators(rng),
+        collection: rng.option(collection),
+        uses: rng.option(uses),
+    }
+}
+
+// conversions to the types of this crate, borrowing from the reference values
+
+pub fn to_creators(creators: &Option<Vec<mpl::Creator>>) -> Option<Vec<Creator>> {
+    creators.as_ref().map(|creators| {
+        creators
+            .iter()
+            .map(|creator| Creator {
+                address: creator.address,
+                verified: creator.verified as u8,
+                share: creator.share,
+            })
+            .collect()
+    })
+}
+
+pub fn to_collection(collection: &mpl::Collection) -> Collection {
+    Collection {
+        verified: collection.verified as u8,
+        key: collection.key,
+    }
+}
+
+pub fn to_use_method(use_method: mpl::UseMethod) -> UseMethod {
+    match use_method {
+        mpl::UseMethod::Burn => UseMethod::Burn,
+        mpl::UseMethod::Multiple => UseMethod::Multiple,
+        mpl::UseMethod::Single => UseMethod::Single,
+    }
+}
+
+pub fn to_uses(uses: &mpl::Uses) -> Uses {
+    Uses {
+        use_method: to_use_method(uses.use_method),
+        remaining: uses.remaining,
+        total: uses.total,
+    }
+}
+
+pub fn to_collection_details(details: &mpl::CollectionDetails) -> CollectionDetails {
+    match details {
+        mpl::CollectionDetails::V1 { size } => CollectionDetails::V1 { size: *size },
+        mpl::CollectionDetails::V2 { padding } => CollectionDetails::V2 { padding: *padding },
+    }
+}
+
+pub fn to_token_standard(token_standard: mpl::TokenStandard) -> TokenStandard {
+    match token_standard {
+        mpl::TokenStandard::NonFungible => TokenStandard::NonFungible,
+        mpl::TokenStandard::FungibleAsset => TokenStandard::FungibleAsset,
+        mpl::TokenStandard::Fungible => TokenStandard::Fungible,
+        mpl::TokenStandard::NonFungibleEdition => TokenStandard::NonFungibleEdition,
+        mpl::TokenStandard::ProgrammableNonFungible => TokenStandard::ProgrammableNonFungible,
+        mpl::TokenStandard::ProgrammableNonFungibleEdition => {
+            TokenStandard::ProgrammableNonFungibleEdition
+        }
+    }
+}
+
+pub fn to_print_supply(print_supply: &mpl::PrintSupply) -> PrintSupply {
+    match print_supply {
+        mpl::PrintSupply::Zero => PrintSupply::Zero,
+        mpl::PrintSupply::Limited(num) => PrintSupply::Limited(*num),
+        mpl::PrintSupply::Unlimited => PrintSupply::Unlimited,
+    }
+}
+
+pub fn to_asset_data<'a>(
+    asset_data: &'a mpl::AssetData,
+    creators: &'a Option<Vec<Creator>>,
+) -> AssetData<'a> {
+    AssetData {
+        name: asset_data.name.as_bytes(),
+        symbol: asset_data.symbol.as_bytes(),
+        uri: asset_data.uri.as_bytes(),
+        seller_fee_basis_points: asset_data.seller_fee_basis_points,
+        creators: creators.as_deref(),
+        primary_sale_happened: asset_data.primary_sale_happened,
+        is_mutable: asset_data.is_mutable,
+        token_standard: to_token_standard(asset_data.token_standard),
+        collection: asset_data.collection.as_ref().map(to_collection),
+        uses: asset_data.uses.as_ref().map(to_uses),
+        collection_details: asset_data
+            .collection_details
+            .as_ref()
+            .map(to_collection_details),
+        rule_set: asset_data.rule_set,
+    }
+}
+
+pub fn to_data<'a>(data: &'a mpl::Data, creators: &'a Option<Vec<Creator>>) -> Data<'a> {
+    Data {
+        name: data.name.as_bytes(),
+        symbol: data.symbol.as_bytes(),
+        uri: data.uri.as_bytes(),
+        seller_fee_basis_points: data.seller_fee_basis_points,
+        creators: creators.as_deref(),
+    }
+}
+
+pub fn to_data_v2<'a>(data: &'a mpl::DataV2, creators: &'a Option<Vec<Creator>>) -> DataV2<'a> {
+    DataV2 {
+        name: data.name.as_bytes(),
+        symbol: data.symbol.as_bytes(),
+        uri: data.uri.as_bytes(),
+        seller_fee_basis_points: data.seller_fee_basis_points,
+        creators: creators.as_deref(),
+        collection: data.collection.as_ref().map(to_collection),
+        uses: data.uses.as_ref().map(to_uses),
+    }
+}
+
+// toggles, the reference has one enum per type
+
+pub fn toggle<T>(rng: &mut Rng, f: impl FnOnce(&mut Rng) -> T) -> (u8, Option<T>) {
+    match rng.below(3) {
+        0 => (0, None),
+        1 => (1, None),
+        _ => (2, Some(f(rng))),
+    }
+}
+
+pub fn to_toggle<T, U>((kind, value): &(u8, Option<T>), f: impl FnOnce(&T) -> U) -> Toggle<U> {
+    match (kind, value) {
+        (0, _) => Toggle::None,
+        (1, _) => Toggle::Clear,
+        (_, Some(value)) => Toggle::Set(f(value)),
+        _ => unreachable!(),
+    }
+}
+
+pub fn collection_toggle(toggle: &(u8, Option<mpl::Collection>)) -> mpl::CollectionToggle {
+    match toggle {
+        (0, _) => mpl::CollectionToggle::None,
+        (1, _) => mpl::CollectionToggle::Clear,
+        (_, value) => mpl::CollectionToggle::Set(value.clone().unwrap()),
+    }
+}
+
+pub fn collection_details_toggle(
+    toggle: &(u8, Option<mpl::CollectionDetails>),
+) -> mpl::CollectionDetailsToggle {
+    match toggle {
+        (0, _) => mpl::CollectionDetailsToggle::None,
+        (1, _) => mpl::CollectionDetailsToggle::Clear,
+        (_, value) => mpl::CollectionDetailsToggle::Set(value.clone().unwrap()),
+    }
+}
+
+pub fn uses_toggle(toggle: &(u8, Option<mpl::Uses>)) -> mpl::UsesToggle {
+    match toggle {
+        (0, _) => mpl::UsesToggle::None,
+        (1, _) => mpl::UsesToggle::Clear,
+        (_, value) => mpl::UsesToggle::Set(value.clone().unwrap()),
+    }
+}
+
+pub fn rule_set_toggle(toggle: &(u8, Option<[u8; 32]>)) -> mpl::RuleSetToggle {
+    match toggle {
+        (0, _) => mpl::RuleSetToggle::None,
+        (1, _) => mpl::RuleSetToggle::Clear,
+        (_, value) => mpl::RuleSetToggle::Set(value.unwrap()),
+    }
+}
+
+// authorization data
+
+pub enum Value {
+    Pubkey([u8; 32]),
+    Seeds(Vec<Vec<u8>>),
+    MerkleProof(Vec<[u8; 32]>),
+    Number(u64),
+}
+
+/// Payload entries in the order they were given, keys can repeat
+pub struct Auth {
+    pub entries: Vec<(PayloadKey, Value)>,
+}
+
+pub fn auth(rng: &mut Rng) -> Auth {
+    let entries = (0..rng.below(6))
+        .map(|_| {
+            let key = PayloadKey::SORTED[rng.below(PayloadKey::SORTED.len())];
+            let value = match rng.below(4) {
+                0 => Value::Pubkey(rng.pubkey()),
+                1 => Value::Seeds((0..rng.below(4)).map(|_| rng.bytes(32)).collect()),
+                2 => Value::MerkleProof((0..rng.below(4)).map(|_| rng.pubkey()).collect()),
+                _ => Value::Number(rng.number()),
+            };
+            (key, value)
+        })
+        .collect();
+
+    Auth { entries }
+}
+
+impl Auth {
+    pub fn reference(&self) -> mpl::AuthorizationData {
+        let mut map = HashMap::new();
+
+        for (key, value) in &self.entries {
+            let value = match value {
+                Value::Pubkey(pubkey) => mpl::PayloadType::Pubkey(*pubkey),
+                Value::Seeds(seeds) => mpl::PayloadType::Seeds(mpl::SeedsVec {
+                    seeds: seeds.clone(),
+                }),
+                Value::MerkleProof(proof) => mpl::PayloadType::MerkleProof(mpl::ProofInfo {
+                    proof: proof.clone(),
+                }),
+                Value::Number(num) => mpl::PayloadType::Number(*num),
+            };
+            map.insert(key.as_str().to_string(), value);
+        }
+
+        mpl::AuthorizationData {
+            payload: mpl::Payload { map },
+        }
+    }
+}
+
+pub fn reference_auth(auth: &Option<Auth>) -> Option<mpl::AuthorizationData> {
+    auth.as_ref().map(Auth::reference)
+}
+
+/// Builds the borrowed version of the authorization data, it has to live on the stack of this call
+pub fn with_auth<R>(auth: &Option<Auth>, f: impl FnOnce(Option<AuthorizationData<'_>>) -> R) -> R {
+    let Some(auth) = auth else {
+        return f(None);
+    };
+
+    let seeds: Vec<Vec<&[u8]>> = auth
+        .entries
+        .iter()
+        .map(|(_, value)| match value {
+            Value::Seeds(seeds) => seeds.iter().map(Vec::as_slice).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+
+    let entries: Vec<(PayloadKey, PayloadType)> = auth
+        .entries
+        .iter()
+        .zip(&seeds)
+        .map(|((key, value), seeds)| {
+            let value = match value {
+                Value::Pubkey(pubkey) => PayloadType::Pubkey(*pubkey),
+                Value::Seeds(_) => PayloadType::Seeds(seeds),
+                Value::MerkleProof(proof) => PayloadType::MerkleProof(proof),
+                Value::Number(num) => PayloadType::Number(*num),
+            };
+            (*key, value)
+        })
+        .collect();
+
+    f(Some(AuthorizationData {
+        payload: Payload::Entries(&entries),
+    }))
+}
+
+// checks
+
+/// Serializes with garbage around, so bytes that are skipped or written past the end show up
+pub fn serialize(value: &impl Serialize) -> Vec<u8> {
+    let len = value.serialized_len();
+
+    // exact size, so anything that doesn't fit panics
+    let mut buffer = vec![0xAA; len];
+    let written = value.serialize_to(&mut buffer);
+    assert_eq!(written, len, "serialize_to and serialized_len disagree");
+
+    let mut other = vec![0x55; len];
+    value.serialize_to(&mut other);
+    assert_eq!(buffer, other, "some bytes are never written");
+
+    if len > 0 {
+        assert!(value.try_serialize_to(&mut buffer[..len - 1]).is_err());
+    }
+    assert_eq!(value.try_serialize_to(&mut buffer).unwrap(), len);
+
+    buffer
+}
+
+/// Same bytes as the reference, and decoding them gives back the same bytes
+pub fn check<'a, T: Serialize + Deserialize<'a>>(value: &T, expected: &'a [u8]) {
+    assert_eq!(serialize(value), expected);
+
+    let decoded = T::decode(expected).expect("reference bytes don't decode");
+    assert_eq!(serialize(&decoded), expected);
+
+    // borsh is prefix free, so a cut off encoding never decodes. Only some of the cuts,
+    // big instructions would take a while
+    for len in (0..expected.len()).step_by(1 + expected.len() / 64) {
+        assert!(T::decode(&expected[..len]).is_err());
+    }
+}
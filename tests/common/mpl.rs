@@ -0,0 +1,473 @@
+//! Local copy of the mpl-token-metadata types, with the same borsh layout as the program.
+//! Pubkeys are plain byte arrays, borsh writes them the same way.
+
+use std::collections::HashMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub type Pubkey = [u8; 32];
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum Key {
+    Uninitialized,
+    EditionV1,
+    MasterEditionV1,
+    ReservationListV1,
+    MetadataV1,
+    ReservationListV2,
+    MasterEditionV2,
+    EditionMarker,
+    UseAuthorityRecord,
+    CollectionAuthorityRecord,
+    TokenOwnedEscrow,
+    TokenRecord,
+    MetadataDelegate,
+    EditionMarkerV2,
+    HolderDelegate,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum CollectionDetails {
+    V1 { size: u64 },
+    V2 { padding: [u8; 8] },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum TokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+    ProgrammableNonFungible,
+    ProgrammableNonFungibleEdition,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum PrintSupply {
+    Zero,
+    Limited(u64),
+    Unlimited,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct AssetData {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub token_standard: TokenStandard,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+    pub collection_details: Option<CollectionDetails>,
+    pub rule_set: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Data {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct DataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CreateMetadataAccountArgsV3 {
+    pub data: DataV2,
+    pub is_mutable: bool,
+    pub collection_details: Option<CollectionDetails>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CreateMasterEditionArgs {
+    pub max_supply: Option<u64>,
+}
+
+// mpl-token-auth-rules
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct SeedsVec {
+    pub seeds: Vec<Vec<u8>>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct ProofInfo {
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum PayloadType {
+    Pubkey(Pubkey),
+    Seeds(SeedsVec),
+    MerkleProof(ProofInfo),
+    Number(u64),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Payload {
+    pub map: HashMap<String, PayloadType>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct AuthorizationData {
+    pub payload: Payload,
+}
+
+// instruction args
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum CreateArgs {
+    V1 {
+        asset_data: AssetData,
+        decimals: Option<u8>,
+        print_supply: Option<PrintSupply>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum MintArgs {
+    V1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum TransferArgs {
+    V1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum BurnArgs {
+    V1 { amount: u64 },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum VerificationArgs {
+    CreatorV1,
+    CollectionV1,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum LockArgs {
+    V1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum UseArgs {
+    V1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum UnlockArgs {
+    V1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum DelegateArgs {
+    CollectionV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    SaleV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+    TransferV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+    DataV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    UtilityV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+    StakingV1 {
+        amount: u64,
+        authorization_data: Option<AuthorizationData>,
+    },
+    StandardV1 {
+        amount: u64,
+    },
+    LockedTransferV1 {
+        amount: u64,
+        locked_address: Pubkey,
+        authorization_data: Option<AuthorizationData>,
+    },
+    ProgrammableConfigV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    AuthorityItemV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    DataItemV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    CollectionItemV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    ProgrammableConfigItemV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+    PrintDelegateV1 {
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum RevokeArgs {
+    CollectionV1,
+    SaleV1,
+    TransferV1,
+    DataV1,
+    UtilityV1,
+    StakingV1,
+    StandardV1,
+    LockedTransferV1,
+    ProgrammableConfigV1,
+    MigrationV1,
+    AuthorityItemV1,
+    DataItemV1,
+    CollectionItemV1,
+    ProgrammableConfigItemV1,
+    PrintDelegateV1,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum MigrationType {
+    CollectionV1,
+    ProgrammableV1,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum MigrateArgs {
+    V1 {
+        migration_type: MigrationType,
+        rule_set: Option<Pubkey>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum PrintArgs {
+    V1 { edition: u64 },
+    V2 { edition: u64 },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum CollectionToggle {
+    None,
+    Clear,
+    Set(Collection),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum CollectionDetailsToggle {
+    None,
+    Clear,
+    Set(CollectionDetails),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum UsesToggle {
+    None,
+    Clear,
+    Set(Uses),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum RuleSetToggle {
+    None,
+    Clear,
+    Set(Pubkey),
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum UpdateArgs {
+    V1 {
+        new_update_authority: Option<Pubkey>,
+        data: Option<Data>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        collection: CollectionToggle,
+        collection_details: CollectionDetailsToggle,
+        uses: UsesToggle,
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsUpdateAuthorityV2 {
+        new_update_authority: Option<Pubkey>,
+        data: Option<Data>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        collection: CollectionToggle,
+        collection_details: CollectionDetailsToggle,
+        uses: UsesToggle,
+        rule_set: RuleSetToggle,
+        token_standard: Option<TokenStandard>,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsAuthorityItemDelegateV2 {
+        new_update_authority: Option<Pubkey>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+        token_standard: Option<TokenStandard>,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsCollectionDelegateV2 {
+        collection: CollectionToggle,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsDataDelegateV2 {
+        data: Option<Data>,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsProgrammableConfigDelegateV2 {
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsDataItemDelegateV2 {
+        data: Option<Data>,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsCollectionItemDelegateV2 {
+        collection: CollectionToggle,
+        authorization_data: Option<AuthorizationData>,
+    },
+    AsProgrammableConfigItemDelegateV2 {
+        rule_set: RuleSetToggle,
+        authorization_data: Option<AuthorizationData>,
+    },
+}
+
+// accounts
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub enum ProgrammableConfig {
+    V1 { rule_set: Option<Pubkey> },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct Metadata {
+    pub key: Key,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub data: Data,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<TokenStandard>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+    pub collection_details: Option<CollectionDetails>,
+    pub programmable_config: Option<ProgrammableConfig>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MasterEditionV2 {
+    pub key: Key,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MasterEditionV1 {
+    pub key: Key,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+    pub printing_mint: Pubkey,
+    pub one_time_printing_authorization_mint: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Edition {
+    pub key: Key,
+    pub parent: Pubkey,
+    pub edition: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum TokenState {
+    Unlocked,
+    Locked,
+    Listed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum TokenDelegateRole {
+    Sale,
+    Transfer,
+    Utility,
+    Staking,
+    Standard,
+    LockedTransfer,
+    Migration,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TokenRecord {
+    pub key: Key,
+    pub bump: u8,
+    pub state: TokenState,
+    pub rule_set_revision: Option<u64>,
+    pub delegate: Option<Pubkey>,
+    pub delegate_role: Option<TokenDelegateRole>,
+    pub locked_transfer: Option<Pubkey>,
+}
+
+/// The program's instruction is an enum, so the data is its index followed by the args
+pub fn instruction_data(discriminator: u8, args: &impl BorshSerialize) -> Vec<u8> {
+    let mut data = vec![discriminator];
+    data.extend(borsh::to_vec(args).unwrap());
+    data
+}
@@ -0,0 +1,695 @@
+//! Every CPI struct sends the accounts of the table in its docs, in order and with the right flags,
+//! and passes an account info for each of its account metas.
+//! Off chain, `invoke_signed` runs all of pinocchio's checks and only skips the syscall,
+//! so a missing or misplaced account info shows up as an error here
+
+mod common;
+
+use common::{
+    accounts::{Accounts, TestAccount},
+    mpl, *,
+};
+use pinocchio::{account_info::AccountInfo, instruction::AccountMeta, ProgramResult};
+use pnft_pinocchio::{
+    data::{
+        burn::BurnInstructionData,
+        create::CreateAssetInstructionData,
+        create_master_edition_v3::CreateMasterEditionV3InstructionData,
+        create_metadata_account_v3::CreateMetadataAccountV3InstructionData,
+        delegate::DelegateInstructionData,
+        lock::LockInstructionData,
+        migrate::{MigrateArgs, MigrateInstructionData, MigrationType},
+        mint::MintInstructionData,
+        print::{PrintArgs, PrintInstructionData},
+        revoke::RevokeInstructionData,
+        transfer::{TransferArgs, TransferInstructionData},
+        unlock::UnlockInstructionData,
+        unverify::UnverifyInstructionData,
+        update::UpdateInstructionData,
+        use_asset::UseInstructionData,
+        verify::{VerificationArgs, VerifyInstructionData},
+        Deserialize,
+    },
+    instructions::{
+        burn::Burn,
+        create::CreateAsset,
+        create_master_edition_v3::CreateMasterEditionV3,
+        create_metadata_account_v3::CreateMetadataAccountV3,
+        delegate::{Delegate, DelegateAccounts},
+        lock::{Lock, LockAccounts},
+        migrate::Migrate,
+        mint::MintAsset,
+        print::Print,
+        revoke::Revoke,
+        transfer::Transfer,
+        unlock::Unlock,
+        unverify::Unverify,
+        update::Update,
+        use_asset::UseAsset,
+        verify::Verify,
+    },
+};
+
+/// Checks a CPI against the table in its docs, given as the flags of each account:
+/// `w` writable, `s` signer, `?` optional.
+///
+/// The CPI is built once with every optional account set and once with none of them,
+/// omitted accounts have to be replaced by the last one, token metadata.
+/// Invoking it also makes pinocchio check the account infos against the metas
+fn check_cpi<const N: usize>(
+    table: [&str; N],
+    f: impl for<'a> Fn(
+        &dyn Fn(usize) -> Option<&'a AccountInfo>,
+        &'a Accounts,
+    ) -> ([AccountMeta<'a>; N], ProgramResult),
+) {
+    let accounts = Accounts::new(N);
+
+    for set in [true, false] {
+        let optional = |i| set.then(|| accounts.get(i));
+        let (metas, result) = f(&optional, &accounts);
+        assert_eq!(result, Ok(()), "optional accounts set: {set}");
+
+        for (i, (meta, flags)) in metas.iter().zip(table).enumerate() {
+            let omitted = !set && flags.contains('?');
+            let key = accounts.get(if omitted { N - 1 } else { i }).key();
+
+            assert_eq!(
+                meta.pubkey, key,
+                "account {i}, optional accounts set: {set}"
+            );
+            assert_eq!(
+                meta.is_writable,
+                !omitted && flags.contains('w'),
+                "account {i} writable, optional accounts set: {set}"
+            );
+            assert_eq!(
+                meta.is_signer,
+                !omitted && flags.contains('s'),
+                "account {i} signer, optional accounts set: {set}"
+            );
+        }
+    }
+}
+
+#[test]
+fn create() {
+    let mut rng = Rng::new(200);
+    let asset_data = common::asset_data(&mut rng);
+    let creators = to_creators(&asset_data.creators);
+    let data = CreateAssetInstructionData {
+        asset_data: to_asset_data(&asset_data, &creators),
+        decimals: Some(0),
+        print_supply: None,
+    };
+
+    check_cpi(
+        ["w", "w?", "ws", "s", "ws", "", "", "", "?", ""],
+        |optional, a| {
+            let cpi = CreateAsset {
+                metadata: a.get(0),
+                master_edition: optional(1),
+                mint: a.get(2),
+                authority: a.get(3),
+                payer: a.get(4),
+                update_authority: a.get(5),
+                system_program: a.get(6),
+                sysvar_instructions: a.get(7),
+                token_program: optional(8),
+                mpl_token_metadata: a.get(9),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(&data, &mut [0; CreateAssetInstructionData::MAX_LEN]),
+            )
+        },
+    );
+}
+
+#[test]
+fn create_mint_signs_only_when_created() {
+    // a mint that doesn't exist yet has no data
+    for (mint_len, signer) in [(0, true), (82, false)] {
+        let accounts: Vec<_> = (0..10)
+            .map(|i| TestAccount {
+                key: [i as u8 + 1; 32],
+                data_len: if i == 2 { mint_len } else { 0 },
+                ..Default::default()
+            })
+            .collect();
+        let a = Accounts::build(&accounts);
+
+        let metas = CreateAsset {
+            metadata: a.get(0),
+            master_edition: Some(a.get(1)),
+            mint: a.get(2),
+            authority: a.get(3),
+            payer: a.get(4),
+            update_authority: a.get(5),
+            system_program: a.get(6),
+            sysvar_instructions: a.get(7),
+            token_program: Some(a.get(8)),
+            mpl_token_metadata: a.get(9),
+        }
+        .account_metas();
+
+        assert_eq!(metas[2].pubkey, a.get(2).key());
+        assert!(metas[2].is_writable);
+        assert_eq!(metas[2].is_signer, signer, "mint data length: {mint_len}");
+    }
+}
+
+#[test]
+fn create_metadata_account_v3() {
+    let mut rng = Rng::new(201);
+    let reference = common::data_v2(&mut rng);
+    let creators = to_creators(&reference.creators);
+    let data = CreateMetadataAccountV3InstructionData {
+        data: to_data_v2(&reference, &creators),
+        is_mutable: true,
+        collection_details: None,
+    };
+
+    for update_authority_is_signer in [true, false] {
+        let update_authority = if update_authority_is_signer { "s" } else { "" };
+        let table = ["w", "", "s", "ws", update_authority, "", "?", ""];
+
+        check_cpi(table, |optional, a| {
+            let cpi = CreateMetadataAccountV3 {
+                metadata: a.get(0),
+                mint: a.get(1),
+                mint_authority: a.get(2),
+                payer: a.get(3),
+                update_authority: a.get(4),
+                update_authority_is_signer,
+                system_program: a.get(5),
+                rent: optional(6),
+                mpl_token_metadata: a.get(7),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(
+                    &data,
+                    &mut [0; CreateMetadataAccountV3InstructionData::MAX_LEN],
+                ),
+            )
+        });
+    }
+}
+
+#[test]
+fn create_master_edition_v3() {
+    let data = CreateMasterEditionV3InstructionData {
+        max_supply: Some(0),
+    };
+
+    check_cpi(
+        ["w", "w", "s", "s", "ws", "w", "", "", "?", ""],
+        |optional, a| {
+            let cpi = CreateMasterEditionV3 {
+                master_edition: a.get(0),
+                mint: a.get(1),
+                update_authority: a.get(2),
+                mint_authority: a.get(3),
+                payer: a.get(4),
+                metadata: a.get(5),
+                token_program: a.get(6),
+                system_program: a.get(7),
+                rent: optional(8),
+                mpl_token_metadata: a.get(9),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(
+                    &data,
+                    &mut [0; CreateMasterEditionV3InstructionData::MAX_LEN],
+                ),
+            )
+        },
+    );
+}
+
+#[test]
+fn mint() {
+    let data = MintInstructionData {
+        amount: 1,
+        authorization_data: None,
+    };
+
+    check_cpi(
+        [
+            "w", "?", "", "w?", "w?", "w", "s", "?", "ws", "", "", "", "", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = MintAsset {
+                token_account: a.get(0),
+                owner: optional(1),
+                metadata: a.get(2),
+                master_edition: optional(3),
+                token_record: optional(4),
+                mint: a.get(5),
+                authority: a.get(6),
+                metadata_delegate_record: optional(7),
+                payer: a.get(8),
+                system_program: a.get(9),
+                sysvar_instructions: a.get(10),
+                token_program: a.get(11),
+                associated_token_program: a.get(12),
+                auth_rules_program: optional(13),
+                auth_rules: optional(14),
+                mpl_token_metadata: a.get(15),
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn transfer() {
+    let data = TransferInstructionData {
+        args: TransferArgs::V1 {
+            amount: 1,
+            authorization_data: None,
+        },
+    };
+
+    check_cpi(
+        [
+            "w", "", "w", "", "", "w", "?", "w?", "w?", "s", "ws", "", "", "", "", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Transfer {
+                src_token_account: a.get(0),
+                owner: a.get(1),
+                dest_token_account: a.get(2),
+                new_owner: a.get(3),
+                mint: a.get(4),
+                metadata: a.get(5),
+                edition: optional(6),
+                owner_token_record: optional(7),
+                dest_token_record: optional(8),
+                authority: a.get(9),
+                payer: a.get(10),
+                system_program: a.get(11),
+                sysvar_instructions: a.get(12),
+                token_program: a.get(13),
+                associated_token_program: a.get(14),
+                auth_rules_program: optional(15),
+                auth_rules: optional(16),
+                mpl_token_metadata: a.get(17),
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn burn() {
+    let data = BurnInstructionData { amount: 1 };
+
+    check_cpi(
+        [
+            "ws", "w?", "w", "w?", "w", "w", "w?", "?", "?", "w?", "w?", "", "", "", "",
+        ],
+        |optional, a| {
+            let cpi = Burn {
+                authority: a.get(0),
+                collection_metadata: optional(1),
+                metadata: a.get(2),
+                edition: optional(3),
+                mint: a.get(4),
+                token_account: a.get(5),
+                master_edition: optional(6),
+                master_edition_mint: optional(7),
+                master_edition_token_account: optional(8),
+                edition_marker: optional(9),
+                token_record: optional(10),
+                system_program: a.get(11),
+                sysvar_instructions: a.get(12),
+                spl_token_program: a.get(13),
+                mpl_token_metadata: a.get(14),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(&data, &mut [0; BurnInstructionData::MAX_LEN]),
+            )
+        },
+    );
+}
+
+#[test]
+fn delegate() {
+    let bytes = mpl::instruction_data(44, &mpl::DelegateArgs::StandardV1 { amount: 1 });
+    let data = DelegateInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        [
+            "w?", "", "w", "?", "w?", "", "w?", "s", "ws", "", "", "?", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Delegate {
+                accounts: DelegateAccounts {
+                    delegate_record: optional(0),
+                    delegate: a.get(1),
+                    metadata: a.get(2),
+                    master_edition: optional(3),
+                    token_record: optional(4),
+                    mint: a.get(5),
+                    token_account: optional(6),
+                    authority: a.get(7),
+                    payer: a.get(8),
+                    system_program: a.get(9),
+                    sysvar_instructions: a.get(10),
+                    spl_token_program: optional(11),
+                    auth_rules_program: optional(12),
+                    auth_rules: optional(13),
+                    mpl_token_metadata: a.get(14),
+                },
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn revoke() {
+    let bytes = mpl::instruction_data(45, &mpl::RevokeArgs::StandardV1);
+    let data = RevokeInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        [
+            "w?", "", "w", "?", "w?", "", "w?", "s", "ws", "", "", "?", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Revoke {
+                accounts: DelegateAccounts {
+                    delegate_record: optional(0),
+                    delegate: a.get(1),
+                    metadata: a.get(2),
+                    master_edition: optional(3),
+                    token_record: optional(4),
+                    mint: a.get(5),
+                    token_account: optional(6),
+                    authority: a.get(7),
+                    payer: a.get(8),
+                    system_program: a.get(9),
+                    sysvar_instructions: a.get(10),
+                    spl_token_program: optional(11),
+                    auth_rules_program: optional(12),
+                    auth_rules: optional(13),
+                    mpl_token_metadata: a.get(14),
+                },
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn lock() {
+    let bytes = mpl::instruction_data(
+        46,
+        &mpl::LockArgs::V1 {
+            authorization_data: None,
+        },
+    );
+    let data = LockInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        [
+            "s", "?", "w", "", "w", "?", "w?", "ws", "", "", "?", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Lock {
+                accounts: LockAccounts {
+                    authority: a.get(0),
+                    token_owner: optional(1),
+                    token_account: a.get(2),
+                    mint: a.get(3),
+                    metadata: a.get(4),
+                    edition: optional(5),
+                    token_record: optional(6),
+                    payer: a.get(7),
+                    system_program: a.get(8),
+                    sysvar_instructions: a.get(9),
+                    spl_token_program: optional(10),
+                    auth_rules_program: optional(11),
+                    auth_rules: optional(12),
+                    mpl_token_metadata: a.get(13),
+                },
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn use_asset() {
+    let bytes = mpl::instruction_data(
+        51,
+        &mpl::UseArgs::V1 {
+            authorization_data: None,
+        },
+    );
+    let data = UseInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        [
+            "ws", "w?", "w?", "", "w", "?", "ws", "", "", "?", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = UseAsset {
+                authority: a.get(0),
+                delegate_record: optional(1),
+                token_account: optional(2),
+                mint: a.get(3),
+                metadata: a.get(4),
+                edition: optional(5),
+                payer: a.get(6),
+                system_program: a.get(7),
+                sysvar_instructions: a.get(8),
+                spl_token_program: optional(9),
+                auth_rules_program: optional(10),
+                auth_rules: optional(11),
+                mpl_token_metadata: a.get(12),
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn unlock() {
+    let bytes = mpl::instruction_data(
+        47,
+        &mpl::UnlockArgs::V1 {
+            authorization_data: None,
+        },
+    );
+    let data = UnlockInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        [
+            "s", "?", "w", "", "w", "?", "w?", "ws", "", "", "?", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Unlock {
+                accounts: LockAccounts {
+                    authority: a.get(0),
+                    token_owner: optional(1),
+                    token_account: a.get(2),
+                    mint: a.get(3),
+                    metadata: a.get(4),
+                    edition: optional(5),
+                    token_record: optional(6),
+                    payer: a.get(7),
+                    system_program: a.get(8),
+                    sysvar_instructions: a.get(9),
+                    spl_token_program: optional(10),
+                    auth_rules_program: optional(11),
+                    auth_rules: optional(12),
+                    mpl_token_metadata: a.get(13),
+                },
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn update() {
+    let bytes = mpl::instruction_data(
+        50,
+        &mpl::UpdateArgs::AsDataDelegateV2 {
+            data: None,
+            authorization_data: None,
+        },
+    );
+    let data = UpdateInstructionData::decode(&bytes).unwrap();
+
+    check_cpi(
+        ["s", "?", "?", "", "w", "?", "ws", "", "", "?", "?", ""],
+        |optional, a| {
+            let cpi = Update {
+                authority: a.get(0),
+                delegate_record: optional(1),
+                token_account: optional(2),
+                mint: a.get(3),
+                metadata: a.get(4),
+                edition: optional(5),
+                payer: a.get(6),
+                system_program: a.get(7),
+                sysvar_instructions: a.get(8),
+                auth_rules_program: optional(9),
+                auth_rules: optional(10),
+                mpl_token_metadata: a.get(11),
+            };
+            (cpi.account_metas(), cpi.invoke(&data, &mut [0; 64]))
+        },
+    );
+}
+
+#[test]
+fn verify() {
+    check_cpi(
+        ["s", "?", "w", "?", "w?", "?", "", "", ""],
+        |optional, a| {
+            let cpi = Verify {
+                authority: a.get(0),
+                delegate_record: optional(1),
+                metadata: a.get(2),
+                collection_mint: optional(3),
+                collection_metadata: optional(4),
+                collection_master_edition: optional(5),
+                system_program: a.get(6),
+                sysvar_instructions: a.get(7),
+                mpl_token_metadata: a.get(8),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(
+                    &VerifyInstructionData {
+                        args: VerificationArgs::CollectionV1,
+                    },
+                    &mut [0; VerifyInstructionData::MAX_LEN],
+                ),
+            )
+        },
+    );
+}
+
+#[test]
+fn unverify() {
+    check_cpi(["s", "?", "w", "?", "w?", "", "", ""], |optional, a| {
+        let cpi = Unverify {
+            authority: a.get(0),
+            delegate_record: optional(1),
+            metadata: a.get(2),
+            collection_mint: optional(3),
+            collection_metadata: optional(4),
+            system_program: a.get(5),
+            sysvar_instructions: a.get(6),
+            mpl_token_metadata: a.get(7),
+        };
+        (
+            cpi.account_metas(),
+            cpi.invoke(
+                &UnverifyInstructionData {
+                    args: VerificationArgs::CollectionV1,
+                },
+                &mut [0; UnverifyInstructionData::MAX_LEN],
+            ),
+        )
+    });
+}
+
+#[test]
+fn migrate() {
+    let data = MigrateInstructionData {
+        args: MigrateArgs::V1 {
+            migration_type: MigrationType::ProgrammableV1,
+            rule_set: None,
+        },
+    };
+
+    check_cpi(
+        [
+            "w", "w", "w", "", "", "ws", "s", "", "?", "w", "", "", "", "?", "?", "",
+        ],
+        |optional, a| {
+            let cpi = Migrate {
+                metadata: a.get(0),
+                edition: a.get(1),
+                token: a.get(2),
+                token_owner: a.get(3),
+                mint: a.get(4),
+                payer: a.get(5),
+                authority: a.get(6),
+                collection_metadata: a.get(7),
+                delegate_record: optional(8),
+                token_record: a.get(9),
+                system_program: a.get(10),
+                sysvar_instructions: a.get(11),
+                spl_token_program: a.get(12),
+                auth_rules_program: optional(13),
+                auth_rules: optional(14),
+                mpl_token_metadata: a.get(15),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(&data, &mut [0; MigrateInstructionData::MAX_LEN]),
+            )
+        },
+    );
+}
+
+#[test]
+fn print() {
+    // V2 lets a holder delegate sign instead of the master token account owner
+    for (args, master_token_account_owner_is_signer) in [
+        (PrintArgs::V1 { edition: 1 }, true),
+        (PrintArgs::V2 { edition: 1 }, false),
+    ] {
+        let data = PrintInstructionData { args };
+        let owner = if master_token_account_owner_is_signer {
+            "s"
+        } else {
+            ""
+        };
+        let table = [
+            "w", "w", "ws", "", "w", "s", "w?", "w", "w", "ws", owner, "", "", "", "", "", "", "",
+            "?", "s?", "",
+        ];
+
+        check_cpi(table, |optional, a| {
+            let cpi = Print {
+                edition_metadata: a.get(0),
+                edition: a.get(1),
+                edition_mint: a.get(2),
+                edition_token_account_owner: a.get(3),
+                edition_token_account: a.get(4),
+                edition_mint_authority: a.get(5),
+                edition_token_record: optional(6),
+                master_edition: a.get(7),
+                edition_marker: a.get(8),
+                payer: a.get(9),
+                master_token_account_owner: a.get(10),
+                master_token_account_owner_is_signer,
+                master_token_account: a.get(11),
+                master_metadata: a.get(12),
+                update_authority: a.get(13),
+                spl_token_program: a.get(14),
+                associated_token_program: a.get(15),
+                sysvar_instructions: a.get(16),
+                system_program: a.get(17),
+                holder_delegate_record: optional(18),
+                delegate: optional(19),
+                mpl_token_metadata: a.get(20),
+            };
+            (
+                cpi.account_metas(),
+                cpi.invoke(&data, &mut [0; PrintInstructionData::MAX_LEN]),
+            )
+        });
+    }
+}
@@ -0,0 +1,190 @@
+//! Reading token metadata instructions back from the instructions sysvar
+
+use pinocchio::{
+    program_error::ProgramError, pubkey::Pubkey,
+    sysvars::instructions::Instructions as InstructionsSysvar,
+};
+use pnft_pinocchio::{
+    data::{
+        transfer::{TransferArgs, TransferInstructionData},
+        Serialize,
+    },
+    introspection::{find_transfer, Position, TokenMetadataCall, TokenMetadataCalls},
+    TokenMetadataInstruction, MPL_TOKEN_METADATA_ID,
+};
+
+const OTHER_PROGRAM: Pubkey = [7; 32];
+const MINT: Pubkey = [4; 32];
+const OTHER_MINT: Pubkey = [200; 32];
+
+struct Ix {
+    program_id: Pubkey,
+    /// (key, is_signer, is_writable)
+    accounts: Vec<(Pubkey, bool, bool)>,
+    data: Vec<u8>,
+}
+
+/// Same layout the runtime uses for the sysvar data
+fn sysvar_data(instructions: &[Ix], current: u16) -> Vec<u8> {
+    let mut data = (instructions.len() as u16).to_le_bytes().to_vec();
+    let mut offsets_at = data.len();
+    data.resize(data.len() + 2 * instructions.len(), 0);
+
+    for ix in instructions {
+        let offset = data.len() as u16;
+        data[offsets_at..offsets_at + 2].copy_from_slice(&offset.to_le_bytes());
+        offsets_at += 2;
+
+        data.extend((ix.accounts.len() as u16).to_le_bytes());
+        for (key, is_signer, is_writable) in &ix.accounts {
+            data.push(*is_signer as u8 | (*is_writable as u8) << 1);
+            data.extend(key);
+        }
+        data.extend(ix.program_id);
+        data.extend((ix.data.len() as u16).to_le_bytes());
+        data.extend(&ix.data);
+    }
+
+    data.extend(current.to_le_bytes());
+    data
+}
+
+/// A Transfer with `mint` at its place, the other accounts get their index as key
+fn transfer(mint: Pubkey, amount: u64) -> Ix {
+    let accounts = (0..17u8)
+        .map(|i| match i {
+            4 => (mint, false, false),
+            _ => ([i; 32], i == 9, i < 3),
+        })
+        .collect();
+
+    let data = TransferInstructionData {
+        args: TransferArgs::V1 {
+            amount,
+            authorization_data: None,
+        },
+    };
+    let mut buffer = vec![0; data.serialized_len()];
+    data.serialize_to(&mut buffer);
+
+    Ix {
+        program_id: MPL_TOKEN_METADATA_ID,
+        accounts,
+        data: buffer,
+    }
+}
+
+fn amount(call: &TokenMetadataCall) -> u64 {
+    match &call.instruction {
+        TokenMetadataInstruction::Transfer(data) => match data.args {
+            TransferArgs::V1 { amount, .. } => amount,
+        },
+        _ => panic!("not a transfer"),
+    }
+}
+
+#[test]
+fn token_metadata_calls() {
+    let data = sysvar_data(
+        &[
+            transfer(OTHER_MINT, 1),
+            Ix {
+                program_id: OTHER_PROGRAM,
+                accounts: vec![],
+                data: vec![1, 2, 3],
+            },
+            transfer(MINT, 2),
+            // the current one
+            transfer(MINT, 3),
+            Ix {
+                program_id: MPL_TOKEN_METADATA_ID,
+                accounts: vec![(MINT, true, true)],
+                data: vec![250, 1],
+            },
+        ],
+        3,
+    );
+    let sysvar = unsafe { InstructionsSysvar::new_unchecked(data.as_slice()) };
+
+    let calls: Vec<_> = TokenMetadataCalls::new(&sysvar, Position::Anywhere)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(calls.len(), 3);
+
+    assert_eq!(calls[0].index, 0);
+    assert_eq!(calls[0].mint(), Some(&OTHER_MINT));
+
+    assert_eq!(calls[1].index, 2);
+    assert_eq!(amount(&calls[1]), 2);
+    assert_eq!(calls[1].mint(), Some(&MINT));
+    let authority = calls[1].account_meta(9).unwrap();
+    assert!(authority.is_signer() && !authority.is_writable());
+    assert!(calls[1].account_meta(17).is_none());
+
+    assert_eq!(calls[2].index, 4);
+    assert!(matches!(
+        calls[2].instruction,
+        TokenMetadataInstruction::Unknown(250)
+    ));
+    assert_eq!(calls[2].account(0), Some(&MINT));
+    assert_eq!(calls[2].mint(), None);
+
+    let found = find_transfer(&sysvar, &MINT, Position::Before)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.index, 2);
+    assert!(find_transfer(&sysvar, &MINT, Position::After)
+        .unwrap()
+        .is_none());
+    let found = find_transfer(&sysvar, &OTHER_MINT, Position::Anywhere)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.index, 0);
+    assert!(find_transfer(&sysvar, &OTHER_MINT, Position::After)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn undecodable_instructions_are_errors() {
+    // trailing bytes, the program can't have run it but it could still be a transfer of the mint
+    let mut trailing = transfer(MINT, 2);
+    trailing.data.push(0);
+
+    let data = sysvar_data(
+        &[
+            transfer(MINT, 1),
+            trailing,
+            // the current one
+            transfer(MINT, 3),
+            Ix {
+                program_id: MPL_TOKEN_METADATA_ID,
+                accounts: vec![],
+                data: vec![],
+            },
+        ],
+        2,
+    );
+    let sysvar = unsafe { InstructionsSysvar::new_unchecked(data.as_slice()) };
+
+    let mut calls = TokenMetadataCalls::new(&sysvar, Position::Anywhere);
+    assert_eq!(calls.next().unwrap().unwrap().index, 0);
+    assert!(calls.next().unwrap().err() == Some(ProgramError::InvalidInstructionData));
+    assert!(calls.next().unwrap().err() == Some(ProgramError::InvalidInstructionData));
+    assert!(calls.next().is_none());
+
+    // found before reaching the undecodable one
+    let found = find_transfer(&sysvar, &MINT, Position::Before)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.index, 0);
+
+    assert!(
+        find_transfer(&sysvar, &OTHER_MINT, Position::Before).err()
+            == Some(ProgramError::InvalidInstructionData)
+    );
+    assert!(
+        find_transfer(&sysvar, &MINT, Position::After).err()
+            == Some(ProgramError::InvalidInstructionData)
+    );
+}
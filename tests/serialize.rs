@@ -0,0 +1,740 @@
+//! Every `Serialize` impl against the borsh encoding of the mpl types
+
+mod common;
+
+use common::{mpl, *};
+use pnft_pinocchio::data::{
+    burn::BurnInstructionData,
+    create::{Collection, CollectionDetails, CreateAssetInstructionData, PrintSupply, Uses},
+    create_master_edition_v3::CreateMasterEditionV3InstructionData,
+    create_metadata_account_v3::CreateMetadataAccountV3InstructionData,
+    delegate::{DelegateArgs, DelegateInstructionData},
+    lock::{LockArgs, LockInstructionData},
+    migrate::{MigrateArgs, MigrateInstructionData, MigrationType},
+    mint::MintInstructionData,
+    print::{PrintArgs, PrintInstructionData},
+    revoke::{RevokeArgs, RevokeInstructionData},
+    transfer::{TransferArgs, TransferInstructionData},
+    unlock::{UnlockArgs, UnlockInstructionData},
+    unverify::UnverifyInstructionData,
+    update::{UpdateArgs, UpdateInstructionData},
+    use_asset::{UseArgs, UseInstructionData},
+    verify::{VerificationArgs, VerifyInstructionData},
+};
+
+#[test]
+fn token_standard() {
+    for token_standard in TOKEN_STANDARDS {
+        check(
+            &to_token_standard(token_standard),
+            &borsh::to_vec(&token_standard).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn use_method() {
+    for use_method in [
+        mpl::UseMethod::Burn,
+        mpl::UseMethod::Multiple,
+        mpl::UseMethod::Single,
+    ] {
+        check(
+            &to_use_method(use_method),
+            &borsh::to_vec(&use_method).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn print_supply() {
+    let mut rng = Rng::new(1);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::print_supply(&mut rng);
+        check::<PrintSupply>(
+            &to_print_supply(&reference),
+            &borsh::to_vec(&reference).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn collection() {
+    let mut rng = Rng::new(2);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::collection(&mut rng);
+        check::<Collection>(
+            &to_collection(&reference),
+            &borsh::to_vec(&reference).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn uses() {
+    let mut rng = Rng::new(3);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::uses(&mut rng);
+        check::<Uses>(&to_uses(&reference), &borsh::to_vec(&reference).unwrap());
+    }
+}
+
+#[test]
+fn collection_details() {
+    let mut rng = Rng::new(4);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::collection_details(&mut rng);
+        check::<CollectionDetails>(
+            &to_collection_details(&reference),
+            &borsh::to_vec(&reference).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn asset_data() {
+    let mut rng = Rng::new(5);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::asset_data(&mut rng);
+        let creators = to_creators(&reference.creators);
+        check(
+            &to_asset_data(&reference, &creators),
+            &borsh::to_vec(&reference).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn data() {
+    let mut rng = Rng::new(6);
+
+    for _ in 0..ITERATIONS {
+        let reference = common::data(&mut rng);
+        let creators = to_creators(&reference.creators);
+        check(
+            &to_data(&reference, &creators),
+            &borsh::to_vec(&reference).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn authorization_data() {
+    let mut rng = Rng::new(7);
+
+    for _ in 0..ITERATIONS {
+        let auth = Some(common::auth(&mut rng));
+        let expected = borsh::to_vec(&reference_auth(&auth)).unwrap();
+        with_auth(&auth, |ours| check(&ours, &expected));
+    }
+}
+
+#[test]
+fn create() {
+    let mut rng = Rng::new(8);
+
+    for _ in 0..ITERATIONS {
+        let asset_data = common::asset_data(&mut rng);
+        let decimals = rng.option(Rng::u8);
+        let print_supply = rng.option(common::print_supply);
+        let creators = to_creators(&asset_data.creators);
+
+        let ours = CreateAssetInstructionData {
+            asset_data: to_asset_data(&asset_data, &creators),
+            decimals,
+            print_supply: print_supply.as_ref().map(to_print_supply),
+        };
+        let expected = mpl::instruction_data(
+            42,
+            &mpl::CreateArgs::V1 {
+                asset_data: asset_data.clone(),
+                decimals,
+                print_supply,
+            },
+        );
+
+        check(&ours, &expected);
+    }
+}
+
+#[test]
+fn create_max_len() {
+    // every string at its max length, every option set
+    let mut rng = Rng::new(9);
+    let asset_data = mpl::AssetData {
+        name: "n".repeat(pnft_pinocchio::data::nft::MAX_NAME_LENGTH),
+        symbol: "s".repeat(pnft_pinocchio::data::nft::MAX_SYMBOL_LENGTH),
+        uri: "u".repeat(pnft_pinocchio::data::nft::MAX_URI_LENGTH),
+        seller_fee_basis_points: 10_000,
+        creators: Some(
+            (0..pnft_pinocchio::data::nft::MAX_CREATOR_LIMIT)
+                .map(|_| mpl::Creator {
+                    address: rng.pubkey(),
+                    verified: true,
+                    share: 20,
+                })
+                .collect(),
+        ),
+        primary_sale_happened: true,
+        is_mutable: true,
+        token_standard: mpl::TokenStandard::ProgrammableNonFungible,
+        collection: Some(common::collection(&mut rng)),
+        uses: Some(common::uses(&mut rng)),
+        collection_details: Some(mpl::CollectionDetails::V1 { size: 1 }),
+        rule_set: Some(rng.pubkey()),
+    };
+    let creators = to_creators(&asset_data.creators);
+
+    let ours = CreateAssetInstructionData {
+        asset_data: to_asset_data(&asset_data, &creators),
+        decimals: Some(0),
+        print_supply: Some(PrintSupply::Limited(1)),
+    };
+
+    assert_eq!(serialize(&ours).len(), CreateAssetInstructionData::MAX_LEN);
+}
+
+#[test]
+fn create_metadata_account_v3() {
+    let mut rng = Rng::new(19);
+
+    for _ in 0..ITERATIONS {
+        let data = common::data_v2(&mut rng);
+        let is_mutable = rng.bool();
+        let collection_details = rng.option(common::collection_details);
+        let creators = to_creators(&data.creators);
+
+        let ours = CreateMetadataAccountV3InstructionData {
+            data: to_data_v2(&data, &creators),
+            is_mutable,
+            collection_details: collection_details.as_ref().map(to_collection_details),
+        };
+        let expected = mpl::instruction_data(
+            33,
+            &mpl::CreateMetadataAccountArgsV3 {
+                data: data.clone(),
+                is_mutable,
+                collection_details,
+            },
+        );
+
+        check(&ours, &expected);
+        assert!(expected.len() <= CreateMetadataAccountV3InstructionData::MAX_LEN);
+    }
+}
+
+#[test]
+fn create_master_edition_v3() {
+    let mut rng = Rng::new(20);
+
+    for _ in 0..ITERATIONS {
+        let max_supply = rng.option(Rng::number);
+
+        let expected = mpl::instruction_data(17, &mpl::CreateMasterEditionArgs { max_supply });
+        check(
+            &CreateMasterEditionV3InstructionData { max_supply },
+            &expected,
+        );
+        assert!(expected.len() <= CreateMasterEditionV3InstructionData::MAX_LEN);
+    }
+}
+
+#[test]
+fn mint() {
+    let mut rng = Rng::new(10);
+
+    for _ in 0..ITERATIONS {
+        let amount = rng.number();
+        let auth = rng.option(common::auth);
+        let expected = mpl::instruction_data(
+            43,
+            &mpl::MintArgs::V1 {
+                amount,
+                authorization_data: reference_auth(&auth),
+            },
+        );
+
+        with_auth(&auth, |authorization_data| {
+            check(
+                &MintInstructionData {
+                    amount,
+                    authorization_data,
+                },
+                &expected,
+            )
+        });
+    }
+}
+
+#[test]
+fn transfer() {
+    let mut rng = Rng::new(11);
+
+    for _ in 0..ITERATIONS {
+        let amount = rng.number();
+        let auth = rng.option(common::auth);
+        let expected = mpl::instruction_data(
+            49,
+            &mpl::TransferArgs::V1 {
+                amount,
+                authorization_data: reference_auth(&auth),
+            },
+        );
+
+        with_auth(&auth, |authorization_data| {
+            check(
+                &TransferInstructionData {
+                    args: TransferArgs::V1 {
+                        amount,
+                        authorization_data,
+                    },
+                },
+                &expected,
+            )
+        });
+    }
+}
+
+#[test]
+fn burn() {
+    let mut rng = Rng::new(12);
+
+    for _ in 0..ITERATIONS {
+        let amount = rng.number();
+        let expected = mpl::instruction_data(41, &mpl::BurnArgs::V1 { amount });
+
+        check(&BurnInstructionData { amount }, &expected);
+        assert_eq!(expected.len(), BurnInstructionData::MAX_LEN);
+    }
+}
+
+#[test]
+fn print() {
+    let mut rng = Rng::new(16);
+
+    for _ in 0..ITERATIONS {
+        let edition = rng.number();
+
+        let expected = mpl::instruction_data(55, &mpl::PrintArgs::V1 { edition });
+        check(
+            &PrintInstructionData {
+                args: PrintArgs::V1 { edition },
+            },
+            &expected,
+        );
+        assert_eq!(expected.len(), PrintInstructionData::MAX_LEN);
+
+        let expected = mpl::instruction_data(55, &mpl::PrintArgs::V2 { edition });
+        check(
+            &PrintInstructionData {
+                args: PrintArgs::V2 { edition },
+            },
+            &expected,
+        );
+    }
+}
+
+#[test]
+fn migrate() {
+    let mut rng = Rng::new(17);
+    let types = [
+        (
+            MigrationType::CollectionV1,
+            mpl::MigrationType::CollectionV1,
+        ),
+        (
+            MigrationType::ProgrammableV1,
+            mpl::MigrationType::ProgrammableV1,
+        ),
+    ];
+
+    for _ in 0..ITERATIONS {
+        let (migration_type, reference_type) = types[rng.below(types.len())];
+        let rule_set = rng.option(Rng::pubkey);
+
+        let expected = mpl::instruction_data(
+            48,
+            &mpl::MigrateArgs::V1 {
+                migration_type: reference_type,
+                rule_set,
+            },
+        );
+        check(
+            &MigrateInstructionData {
+                args: MigrateArgs::V1 {
+                    migration_type,
+                    rule_set,
+                },
+            },
+            &expected,
+        );
+        assert!(expected.len() <= MigrateInstructionData::MAX_LEN);
+    }
+}
+
+#[test]
+fn verify_unverify() {
+    for (ours, reference) in [
+        (
+            VerificationArgs::CreatorV1,
+            mpl::VerificationArgs::CreatorV1,
+        ),
+        (
+            VerificationArgs::CollectionV1,
+            mpl::VerificationArgs::CollectionV1,
+        ),
+    ] {
+        let expected = mpl::instruction_data(52, &reference);
+        check(&VerifyInstructionData { args: ours }, &expected);
+        assert_eq!(expected.len(), VerifyInstructionData::MAX_LEN);
+
+        let expected = mpl::instruction_data(53, &reference);
+        check(&UnverifyInstructionData { args: ours }, &expected);
+        assert_eq!(expected.len(), UnverifyInstructionData::MAX_LEN);
+    }
+}
+
+#[test]
+fn lock_unlock() {
+    let mut rng = Rng::new(13);
+
+    for _ in 0..ITERATIONS {
+        let auth = rng.option(common::auth);
+
+        let expected = mpl::instruction_data(
+            46,
+            &mpl::LockArgs::V1 {
+                authorization_data: reference_auth(&auth),
+            },
+        );
+        with_auth(&auth, |authorization_data| {
+            check(
+                &LockInstructionData {
+                    args: LockArgs::V1 { authorization_data },
+                },
+                &expected,
+            )
+        });
+
+        let expected = mpl::instruction_data(
+            47,
+            &mpl::UnlockArgs::V1 {
+                authorization_data: reference_auth(&auth),
+            },
+        );
+        with_auth(&auth, |authorization_data| {
+            check(
+                &UnlockInstructionData {
+                    args: UnlockArgs::V1 { authorization_data },
+                },
+                &expected,
+            )
+        });
+    }
+}
+
+#[test]
+fn use_asset() {
+    let mut rng = Rng::new(18);
+
+    for _ in 0..ITERATIONS {
+        let auth = rng.option(common::auth);
+
+        let expected = mpl::instruction_data(
+            51,
+            &mpl::UseArgs::V1 {
+                authorization_data: reference_auth(&auth),
+            },
+        );
+        with_auth(&auth, |authorization_data| {
+            check(
+                &UseInstructionData {
+                    args: UseArgs::V1 { authorization_data },
+                },
+                &expected,
+            )
+        });
+    }
+}
+
+#[test]
+fn revoke() {
+    let all = [
+        (RevokeArgs::CollectionV1, mpl::RevokeArgs::CollectionV1),
+        (RevokeArgs::SaleV1, mpl::RevokeArgs::SaleV1),
+        (RevokeArgs::TransferV1, mpl::RevokeArgs::TransferV1),
+        (RevokeArgs::DataV1, mpl::RevokeArgs::DataV1),
+        (RevokeArgs::UtilityV1, mpl::RevokeArgs::UtilityV1),
+        (RevokeArgs::StakingV1, mpl::RevokeArgs::StakingV1),
+        (RevokeArgs::StandardV1, mpl::RevokeArgs::StandardV1),
+        (
+            RevokeArgs::LockedTransferV1,
+            mpl::RevokeArgs::LockedTransferV1,
+        ),
+        (
+            RevokeArgs::ProgrammableConfigV1,
+            mpl::RevokeArgs::ProgrammableConfigV1,
+        ),
+        (RevokeArgs::MigrationV1, mpl::RevokeArgs::MigrationV1),
+        (
+            RevokeArgs::AuthorityItemV1,
+            mpl::RevokeArgs::AuthorityItemV1,
+        ),
+        (RevokeArgs::DataItemV1, mpl::RevokeArgs::DataItemV1),
+        (
+            RevokeArgs::CollectionItemV1,
+            mpl::RevokeArgs::CollectionItemV1,
+        ),
+        (
+            RevokeArgs::ProgrammableConfigItemV1,
+            mpl::RevokeArgs::ProgrammableConfigItemV1,
+        ),
+        (
+            RevokeArgs::PrintDelegateV1,
+            mpl::RevokeArgs::PrintDelegateV1,
+        ),
+    ];
+
+    for (args, reference) in all {
+        check(
+            &RevokeInstructionData { args },
+            &mpl::instruction_data(45, &reference),
+        );
+    }
+}
+
+#[test]
+fn delegate() {
+    let mut rng = Rng::new(14);
+
+    for i in 0..ITERATIONS {
+        let amount = rng.number();
+        let locked_address = rng.pubkey();
+        let auth = rng.option(common::auth);
+        let authorization_data = reference_auth(&auth);
+
+        // go through every variant
+        let reference = match i % 14 {
+            0 => mpl::DelegateArgs::CollectionV1 { authorization_data },
+            1 => mpl::DelegateArgs::SaleV1 {
+                amount,
+                authorization_data,
+            },
+            2 => mpl::DelegateArgs::TransferV1 {
+                amount,
+                authorization_data,
+            },
+            3 => mpl::DelegateArgs::DataV1 { authorization_data },
+            4 => mpl::DelegateArgs::UtilityV1 {
+                amount,
+                authorization_data,
+            },
+            5 => mpl::DelegateArgs::StakingV1 {
+                amount,
+                authorization_data,
+            },
+            6 => mpl::DelegateArgs::StandardV1 { amount },
+            7 => mpl::DelegateArgs::LockedTransferV1 {
+                amount,
+                locked_address,
+                authorization_data,
+            },
+            8 => mpl::DelegateArgs::ProgrammableConfigV1 { authorization_data },
+            9 => mpl::DelegateArgs::AuthorityItemV1 { authorization_data },
+            10 => mpl::DelegateArgs::DataItemV1 { authorization_data },
+            11 => mpl::DelegateArgs::CollectionItemV1 { authorization_data },
+            12 => mpl::DelegateArgs::ProgrammableConfigItemV1 { authorization_data },
+            _ => mpl::DelegateArgs::PrintDelegateV1 { authorization_data },
+        };
+        let expected = mpl::instruction_data(44, &reference);
+
+        with_auth(&auth, |authorization_data| {
+            let args = match i % 14 {
+                0 => DelegateArgs::CollectionV1 { authorization_data },
+                1 => DelegateArgs::SaleV1 {
+                    amount,
+                    authorization_data,
+                },
+                2 => DelegateArgs::TransferV1 {
+                    amount,
+                    authorization_data,
+                },
+                3 => DelegateArgs::DataV1 { authorization_data },
+                4 => DelegateArgs::UtilityV1 {
+                    amount,
+                    authorization_data,
+                },
+                5 => DelegateArgs::StakingV1 {
+                    amount,
+                    authorization_data,
+                },
+                6 => DelegateArgs::StandardV1 { amount },
+                7 => DelegateArgs::LockedTransferV1 {
+                    amount,
+                    locked_address,
+                    authorization_data,
+                },
+                8 => DelegateArgs::ProgrammableConfigV1 { authorization_data },
+                9 => DelegateArgs::AuthorityItemV1 { authorization_data },
+                10 => DelegateArgs::DataItemV1 { authorization_data },
+                11 => DelegateArgs::CollectionItemV1 { authorization_data },
+                12 => DelegateArgs::ProgrammableConfigItemV1 { authorization_data },
+                _ => DelegateArgs::PrintDelegateV1 { authorization_data },
+            };
+
+            check(&DelegateInstructionData { args }, &expected);
+        });
+    }
+}
+
+#[test]
+fn update() {
+    let mut rng = Rng::new(15);
+
+    for i in 0..ITERATIONS {
+        let new_update_authority = rng.option(Rng::pubkey);
+        let data = rng.option(common::data);
+        let primary_sale_happened = rng.option(Rng::bool);
+        let is_mutable = rng.option(Rng::bool);
+        let collection = toggle(&mut rng, common::collection);
+        let collection_details = toggle(&mut rng, common::collection_details);
+        let uses = toggle(&mut rng, common::uses);
+        let rule_set = toggle(&mut rng, Rng::pubkey);
+        let token_standard = rng.option(common::token_standard);
+        let auth = rng.option(common::auth);
+        let authorization_data = reference_auth(&auth);
+
+        // go through every variant
+        let reference = match i % 9 {
+            0 => mpl::UpdateArgs::V1 {
+                new_update_authority,
+                data: data.clone(),
+                primary_sale_happened,
+                is_mutable,
+                collection: collection_toggle(&collection),
+                collection_details: collection_details_toggle(&collection_details),
+                uses: uses_toggle(&uses),
+                rule_set: rule_set_toggle(&rule_set),
+                authorization_data,
+            },
+            1 => mpl::UpdateArgs::AsUpdateAuthorityV2 {
+                new_update_authority,
+                data: data.clone(),
+                primary_sale_happened,
+                is_mutable,
+                collection: collection_toggle(&collection),
+                collection_details: collection_details_toggle(&collection_details),
+                uses: uses_toggle(&uses),
+                rule_set: rule_set_toggle(&rule_set),
+                token_standard,
+                authorization_data,
+            },
+            2 => mpl::UpdateArgs::AsAuthorityItemDelegateV2 {
+                new_update_authority,
+                primary_sale_happened,
+                is_mutable,
+                token_standard,
+                authorization_data,
+            },
+            3 => mpl::UpdateArgs::AsCollectionDelegateV2 {
+                collection: collection_toggle(&collection),
+                authorization_data,
+            },
+            4 => mpl::UpdateArgs::AsDataDelegateV2 {
+                data: data.clone(),
+                authorization_data,
+            },
+            5 => mpl::UpdateArgs::AsProgrammableConfigDelegateV2 {
+                rule_set: rule_set_toggle(&rule_set),
+                authorization_data,
+            },
+            6 => mpl::UpdateArgs::AsDataItemDelegateV2 {
+                data: data.clone(),
+                authorization_data,
+            },
+            7 => mpl::UpdateArgs::AsCollectionItemDelegateV2 {
+                collection: collection_toggle(&collection),
+                authorization_data,
+            },
+            _ => mpl::UpdateArgs::AsProgrammableConfigItemDelegateV2 {
+                rule_set: rule_set_toggle(&rule_set),
+                authorization_data,
+            },
+        };
+        let expected = mpl::instruction_data(50, &reference);
+
+        let creators = data.as_ref().and_then(|data| to_creators(&data.creators));
+        let ours_data = || data.as_ref().map(|data| to_data(data, &creators));
+        let ours_collection = || to_toggle(&collection, to_collection);
+        let ours_collection_details = || to_toggle(&collection_details, to_collection_details);
+        let ours_uses = || to_toggle(&uses, to_uses);
+        let ours_rule_set = || to_toggle(&rule_set, |rule_set| *rule_set);
+        let ours_token_standard = token_standard.map(to_token_standard);
+
+        with_auth(&auth, |authorization_data| {
+            let args = match i % 9 {
+                0 => UpdateArgs::V1 {
+                    new_update_authority,
+                    data: ours_data(),
+                    primary_sale_happened,
+                    is_mutable,
+                    collection: ours_collection(),
+                    collection_details: ours_collection_details(),
+                    uses: ours_uses(),
+                    rule_set: ours_rule_set(),
+                    authorization_data,
+                },
+                1 => UpdateArgs::AsUpdateAuthorityV2 {
+                    new_update_authority,
+                    data: ours_data(),
+                    primary_sale_happened,
+                    is_mutable,
+                    collection: ours_collection(),
+                    collection_details: ours_collection_details(),
+                    uses: ours_uses(),
+                    rule_set: ours_rule_set(),
+                    token_standard: ours_token_standard,
+                    authorization_data,
+                },
+                2 => UpdateArgs::AsAuthorityItemDelegateV2 {
+                    new_update_authority,
+                    primary_sale_happened,
+                    is_mutable,
+                    token_standard: ours_token_standard,
+                    authorization_data,
+                },
+                3 => UpdateArgs::AsCollectionDelegateV2 {
+                    collection: ours_collection(),
+                    authorization_data,
+                },
+                4 => UpdateArgs::AsDataDelegateV2 {
+                    data: ours_data(),
+                    authorization_data,
+                },
+                5 => UpdateArgs::AsProgrammableConfigDelegateV2 {
+                    rule_set: ours_rule_set(),
+                    authorization_data,
+                },
+                6 => UpdateArgs::AsDataItemDelegateV2 {
+                    data: ours_data(),
+                    authorization_data,
+                },
+                7 => UpdateArgs::AsCollectionItemDelegateV2 {
+                    collection: ours_collection(),
+                    authorization_data,
+                },
+                _ => UpdateArgs::AsProgrammableConfigItemDelegateV2 {
+                    rule_set: ours_rule_set(),
+                    authorization_data,
+                },
+            };
+
+            check(&UpdateInstructionData { args }, &expected);
+        });
+    }
+}
@@ -0,0 +1,193 @@
+//! Token program selection of the CPIs that touch token accounts
+
+mod common;
+
+use common::accounts::{Accounts, TestAccount};
+use pinocchio::program_error::ProgramError;
+use pnft_pinocchio::{
+    error::PnftError,
+    instructions::{burn::Burn, create::CreateAsset, mint::MintAsset, transfer::Transfer},
+    token_program::{TokenProgram, SPL_TOKEN_ID, TOKEN_2022_ID},
+};
+
+const OTHER_PROGRAM: [u8; 32] = [200; 32];
+
+/// `count` accounts with different keys. The token program is at `program`,
+/// `owned` are created accounts owned by `owner`
+fn accounts(
+    count: usize,
+    program: (usize, [u8; 32]),
+    owned: &[usize],
+    owner: [u8; 32],
+) -> Accounts {
+    let accounts: Vec<_> = (0..count)
+        .map(|i| TestAccount {
+            key: if i == program.0 {
+                program.1
+            } else {
+                [i as u8 + 1; 32]
+            },
+            owner: if owned.contains(&i) { owner } else { [0; 32] },
+            data_len: if owned.contains(&i) { 82 } else { 0 },
+            ..Default::default()
+        })
+        .collect();
+
+    Accounts::build(&accounts)
+}
+
+fn burn(a: &Accounts) -> Result<TokenProgram, ProgramError> {
+    Burn {
+        authority: a.get(0),
+        collection_metadata: None,
+        metadata: a.get(2),
+        edition: None,
+        mint: a.get(4),
+        token_account: a.get(5),
+        master_edition: None,
+        master_edition_mint: None,
+        master_edition_token_account: None,
+        edition_marker: None,
+        token_record: None,
+        system_program: a.get(11),
+        sysvar_instructions: a.get(12),
+        spl_token_program: a.get(13),
+        mpl_token_metadata: a.get(14),
+    }
+    .check_token_program()
+}
+
+fn transfer(a: &Accounts) -> Result<TokenProgram, ProgramError> {
+    Transfer {
+        src_token_account: a.get(0),
+        owner: a.get(1),
+        dest_token_account: a.get(2),
+        new_owner: a.get(3),
+        mint: a.get(4),
+        metadata: a.get(5),
+        edition: None,
+        owner_token_record: None,
+        dest_token_record: None,
+        authority: a.get(9),
+        payer: a.get(10),
+        system_program: a.get(11),
+        sysvar_instructions: a.get(12),
+        token_program: a.get(13),
+        associated_token_program: a.get(14),
+        auth_rules_program: None,
+        auth_rules: None,
+        mpl_token_metadata: a.get(17),
+    }
+    .check_token_program()
+}
+
+fn mint(a: &Accounts) -> Result<TokenProgram, ProgramError> {
+    MintAsset {
+        token_account: a.get(0),
+        owner: None,
+        metadata: a.get(2),
+        master_edition: None,
+        token_record: None,
+        mint: a.get(5),
+        authority: a.get(6),
+        metadata_delegate_record: None,
+        payer: a.get(8),
+        system_program: a.get(9),
+        sysvar_instructions: a.get(10),
+        token_program: a.get(11),
+        associated_token_program: a.get(12),
+        auth_rules_program: None,
+        auth_rules: None,
+        mpl_token_metadata: a.get(15),
+    }
+    .check_token_program()
+}
+
+fn create(a: &Accounts, with_token_program: bool) -> Result<TokenProgram, ProgramError> {
+    CreateAsset {
+        metadata: a.get(0),
+        master_edition: None,
+        mint: a.get(2),
+        authority: a.get(3),
+        payer: a.get(4),
+        update_authority: a.get(5),
+        system_program: a.get(6),
+        sysvar_instructions: a.get(7),
+        token_program: with_token_program.then(|| a.get(8)),
+        mpl_token_metadata: a.get(9),
+    }
+    .check_token_program()
+}
+
+#[test]
+fn burn_token_program() {
+    for (id, expected) in [
+        (SPL_TOKEN_ID, TokenProgram::SplToken),
+        (TOKEN_2022_ID, TokenProgram::Token2022),
+    ] {
+        let a = accounts(15, (13, id), &[4, 5], id);
+        assert!(burn(&a) == Ok(expected));
+    }
+
+    let a = accounts(15, (13, OTHER_PROGRAM), &[4, 5], OTHER_PROGRAM);
+    assert!(burn(&a) == Err(PnftError::InvalidTokenProgram.into()));
+
+    // token account of the other token program
+    let a = accounts(15, (13, TOKEN_2022_ID), &[4], TOKEN_2022_ID);
+    assert!(burn(&a) == Err(PnftError::TokenProgramMismatch.into()));
+}
+
+#[test]
+fn transfer_token_program() {
+    for (id, expected) in [
+        (SPL_TOKEN_ID, TokenProgram::SplToken),
+        (TOKEN_2022_ID, TokenProgram::Token2022),
+    ] {
+        // destination token account is created by the transfer
+        let a = accounts(18, (13, id), &[0, 4], id);
+        assert!(transfer(&a) == Ok(expected));
+
+        let a = accounts(18, (13, id), &[0, 2, 4], id);
+        assert!(transfer(&a) == Ok(expected));
+    }
+
+    let a = accounts(18, (13, SPL_TOKEN_ID), &[0, 2, 4], TOKEN_2022_ID);
+    assert!(transfer(&a) == Err(PnftError::TokenProgramMismatch.into()));
+
+    let a = accounts(18, (13, OTHER_PROGRAM), &[0, 2, 4], OTHER_PROGRAM);
+    assert!(transfer(&a) == Err(PnftError::InvalidTokenProgram.into()));
+}
+
+#[test]
+fn mint_token_program() {
+    let a = accounts(16, (11, TOKEN_2022_ID), &[5], TOKEN_2022_ID);
+    assert!(mint(&a) == Ok(TokenProgram::Token2022));
+
+    // the mint has to exist already
+    let a = accounts(16, (11, TOKEN_2022_ID), &[], TOKEN_2022_ID);
+    assert!(mint(&a) == Err(PnftError::TokenProgramMismatch.into()));
+}
+
+#[test]
+fn create_token_program() {
+    // the mint doesn't exist yet, create makes it
+    let a = accounts(10, (8, TOKEN_2022_ID), &[], TOKEN_2022_ID);
+    assert!(create(&a, true) == Ok(TokenProgram::Token2022));
+    assert!(create(&a, false) == Ok(TokenProgram::SplToken));
+
+    let a = accounts(10, (8, TOKEN_2022_ID), &[2], SPL_TOKEN_ID);
+    assert!(create(&a, true) == Err(PnftError::TokenProgramMismatch.into()));
+    assert!(create(&a, false) == Ok(TokenProgram::SplToken));
+}
+
+#[test]
+fn token_program_from_owner() {
+    let a = accounts(3, (2, OTHER_PROGRAM), &[0], TOKEN_2022_ID);
+    assert!(TokenProgram::from_owner(a.get(0)) == Ok(TokenProgram::Token2022));
+    let a = accounts(3, (2, OTHER_PROGRAM), &[0], SPL_TOKEN_ID);
+    assert!(TokenProgram::from_owner(a.get(0)) == Ok(TokenProgram::SplToken));
+
+    let a = accounts(3, (2, OTHER_PROGRAM), &[0], OTHER_PROGRAM);
+    assert!(TokenProgram::from_owner(a.get(0)) == Err(PnftError::TokenProgramMismatch.into()));
+    assert!(TokenProgram::from_owner(a.get(1)) == Err(PnftError::TokenProgramMismatch.into()));
+}
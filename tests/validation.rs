@@ -0,0 +1,201 @@
+//! `validate` of the CPI structs. PDA derivation needs the runtime, so it isn't tested here
+
+mod common;
+
+use common::accounts::{Accounts, TestAccount};
+use pinocchio::{
+    program_error::ProgramError, pubkey::Pubkey, sysvars::instructions::INSTRUCTIONS_ID,
+};
+use pnft_pinocchio::{
+    error::PnftError,
+    instructions::{transfer::Transfer, verify::Verify, Validation},
+    token_program::{ASSOCIATED_TOKEN_PROGRAM_ID, SPL_TOKEN_ID},
+    MPL_TOKEN_AUTH_RULES_ID, MPL_TOKEN_METADATA_ID, SYSTEM_PROGRAM_ID,
+};
+
+fn account(i: usize) -> TestAccount {
+    TestAccount {
+        key: [i as u8 + 1; 32],
+        ..Default::default()
+    }
+}
+
+fn signer(mut account: TestAccount) -> TestAccount {
+    account.signer = true;
+    account
+}
+
+fn writable(mut account: TestAccount) -> TestAccount {
+    account.writable = true;
+    account
+}
+
+fn owned(mut account: TestAccount, owner: Pubkey) -> TestAccount {
+    account.owner = owner;
+    account.data_len = 100;
+    account
+}
+
+fn program(id: Pubkey) -> TestAccount {
+    TestAccount {
+        key: id,
+        ..Default::default()
+    }
+}
+
+/// Accounts of a valid transfer of a pNFT to a new wallet
+fn transfer_accounts() -> Vec<TestAccount> {
+    vec![
+        writable(owned(account(0), SPL_TOKEN_ID)),
+        account(1),
+        writable(account(2)),
+        account(3),
+        owned(account(4), SPL_TOKEN_ID),
+        writable(owned(account(5), MPL_TOKEN_METADATA_ID)),
+        owned(account(6), MPL_TOKEN_METADATA_ID),
+        writable(owned(account(7), MPL_TOKEN_METADATA_ID)),
+        writable(account(8)),
+        signer(account(9)),
+        signer(writable(account(10))),
+        program(SYSTEM_PROGRAM_ID),
+        program(INSTRUCTIONS_ID),
+        program(SPL_TOKEN_ID),
+        program(ASSOCIATED_TOKEN_PROGRAM_ID),
+        program(MPL_TOKEN_AUTH_RULES_ID),
+        account(16),
+        program(MPL_TOKEN_METADATA_ID),
+    ]
+}
+
+fn validate_transfer(
+    accounts: &[TestAccount],
+    validation: &Validation,
+) -> Result<(), ProgramError> {
+    let a = Accounts::build(accounts);
+
+    Transfer {
+        src_token_account: a.get(0),
+        owner: a.get(1),
+        dest_token_account: a.get(2),
+        new_owner: a.get(3),
+        mint: a.get(4),
+        metadata: a.get(5),
+        edition: Some(a.get(6)),
+        owner_token_record: Some(a.get(7)),
+        dest_token_record: Some(a.get(8)),
+        authority: a.get(9),
+        payer: a.get(10),
+        system_program: a.get(11),
+        sysvar_instructions: a.get(12),
+        token_program: a.get(13),
+        associated_token_program: a.get(14),
+        auth_rules_program: Some(a.get(15)),
+        auth_rules: Some(a.get(16)),
+        mpl_token_metadata: a.get(17),
+    }
+    .validate(validation)
+}
+
+/// Changes one account of a valid transfer, the validation has to fail with `error`
+fn transfer_fails(index: usize, change: impl FnOnce(TestAccount) -> TestAccount, error: PnftError) {
+    let mut accounts = transfer_accounts();
+    accounts[index] = change(accounts[index]);
+
+    assert!(validate_transfer(&accounts, &Validation::default()) == Err(error.into()));
+}
+
+#[test]
+fn transfer() {
+    assert!(validate_transfer(&transfer_accounts(), &Validation::default()) == Ok(()));
+
+    // authority and payer
+    transfer_fails(
+        9,
+        |a| TestAccount { signer: false, ..a },
+        PnftError::MissingSigner,
+    );
+    transfer_fails(
+        10,
+        |a| TestAccount {
+            writable: false,
+            ..a
+        },
+        PnftError::NotWritable,
+    );
+
+    // token accounts and token metadata accounts
+    transfer_fails(
+        0,
+        |a| TestAccount {
+            writable: false,
+            ..a
+        },
+        PnftError::NotWritable,
+    );
+    transfer_fails(5, |a| owned(a, SYSTEM_PROGRAM_ID), PnftError::InvalidOwner);
+    transfer_fails(6, |a| owned(a, SPL_TOKEN_ID), PnftError::InvalidOwner);
+    transfer_fails(8, |a| owned(a, SYSTEM_PROGRAM_ID), PnftError::InvalidOwner);
+    transfer_fails(
+        4,
+        |a| owned(a, MPL_TOKEN_METADATA_ID),
+        PnftError::TokenProgramMismatch,
+    );
+
+    // programs
+    transfer_fails(11, |_| account(11), PnftError::InvalidProgramId);
+    transfer_fails(12, |_| account(12), PnftError::InvalidProgramId);
+    transfer_fails(13, |_| account(13), PnftError::InvalidTokenProgram);
+    transfer_fails(14, |_| account(14), PnftError::InvalidProgramId);
+    transfer_fails(15, |_| account(15), PnftError::InvalidProgramId);
+    transfer_fails(17, |_| account(17), PnftError::InvalidProgramId);
+}
+
+#[test]
+fn transfer_signed_by_pda() {
+    let mut accounts = transfer_accounts();
+    accounts[9].signer = false;
+
+    let authority = accounts[9].key;
+    let validation = Validation {
+        pda_signers: &[&authority],
+        check_pdas: false,
+    };
+
+    assert!(validate_transfer(&accounts, &validation) == Ok(()));
+}
+
+#[test]
+fn verify() {
+    let validate = |accounts: &[TestAccount]| {
+        let a = Accounts::build(accounts);
+
+        Verify {
+            authority: a.get(0),
+            delegate_record: None,
+            metadata: a.get(2),
+            collection_mint: Some(a.get(3)),
+            collection_metadata: Some(a.get(4)),
+            collection_master_edition: Some(a.get(5)),
+            system_program: a.get(6),
+            sysvar_instructions: a.get(7),
+            mpl_token_metadata: a.get(8),
+        }
+        .validate(&Validation::default())
+    };
+
+    let mut accounts = vec![
+        signer(account(0)),
+        account(1),
+        writable(owned(account(2), MPL_TOKEN_METADATA_ID)),
+        owned(account(3), SPL_TOKEN_ID),
+        writable(owned(account(4), MPL_TOKEN_METADATA_ID)),
+        owned(account(5), MPL_TOKEN_METADATA_ID),
+        program(SYSTEM_PROGRAM_ID),
+        program(INSTRUCTIONS_ID),
+        program(MPL_TOKEN_METADATA_ID),
+    ];
+    assert!(validate(&accounts) == Ok(()));
+
+    accounts[4].writable = false;
+    assert!(validate(&accounts) == Err(PnftError::NotWritable.into()));
+}
@@ -0,0 +1,448 @@
+//! Account views against accounts written by borsh
+
+mod common;
+
+use common::{
+    accounts::{Accounts, TestAccount},
+    mpl, *,
+};
+use pinocchio::program_error::ProgramError;
+use pnft_pinocchio::{
+    data::{
+        create::{CollectionDetails, TokenStandard},
+        edition::{EditionView, MasterEditionView},
+        nft::{
+            Key, MetadataView, ProgrammableConfig, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH,
+            MAX_URI_LENGTH,
+        },
+        token_record::{TokenDelegateRole, TokenRecordView, TokenState},
+        Skip,
+    },
+    error::PnftError,
+    token_program::SPL_TOKEN_ID,
+    MPL_TOKEN_METADATA_ID,
+};
+
+/// Pads the string with `\0` like the program does when creating new accounts
+fn puff(string: &mut String, max_len: usize) {
+    while string.len() < max_len {
+        string.push('\0');
+    }
+}
+
+#[test]
+fn metadata() {
+    let mut rng = Rng::new(100);
+
+    for _ in 0..ITERATIONS {
+        let mut data = common::data(&mut rng);
+        let puffed = rng.bool();
+        if puffed {
+            puff(&mut data.name, MAX_NAME_LENGTH);
+            puff(&mut data.symbol, MAX_SYMBOL_LENGTH);
+            puff(&mut data.uri, MAX_URI_LENGTH);
+        }
+
+        let metadata = mpl::Metadata {
+            key: mpl::Key::MetadataV1,
+            update_authority: rng.pubkey(),
+            mint: rng.pubkey(),
+            data,
+            primary_sale_happened: rng.bool(),
+            is_mutable: rng.bool(),
+            edition_nonce: rng.option(Rng::u8),
+            token_standard: rng.option(common::token_standard),
+            collection: rng.option(common::collection),
+            uses: rng.option(common::uses),
+            collection_details: rng.option(common::collection_details),
+            programmable_config: rng.option(|rng| mpl::ProgrammableConfig::V1 {
+                rule_set: rng.option(Rng::pubkey),
+            }),
+        };
+
+        let mut bytes = borsh::to_vec(&metadata).unwrap();
+        // accounts are usually bigger than what is in them
+        bytes.resize(bytes.len() + rng.below(64), 0);
+
+        let view = MetadataView::new(&bytes).unwrap();
+        assert_eq!(view.is_puffed(), puffed);
+        assert_eq!(view.update_authority().unwrap(), &metadata.update_authority);
+        assert_eq!(view.mint().unwrap(), &metadata.mint);
+        assert_eq!(view.name().unwrap(), metadata.data.name.as_bytes());
+        assert_eq!(view.symbol().unwrap(), metadata.data.symbol.as_bytes());
+        assert_eq!(view.uri().unwrap(), metadata.data.uri.as_bytes());
+        assert_eq!(
+            view.seller_fee_basis_points().unwrap(),
+            metadata.data.seller_fee_basis_points
+        );
+
+        let creators = view.creators().unwrap();
+        let expected = metadata.data.creators.as_ref();
+        assert_eq!(creators.map(<[_]>::len), expected.map(Vec::len));
+        for (creator, expected) in creators
+            .iter()
+            .flat_map(|c| c.iter())
+            .zip(expected.iter().flat_map(|c| c.iter()))
+        {
+            assert_eq!(creator.address, expected.address);
+            assert_eq!(creator.verified, expected.verified as u8);
+            assert_eq!(creator.share, expected.share);
+        }
+
+        assert_eq!(
+            view.primary_sale_happened().unwrap(),
+            metadata.primary_sale_happened
+        );
+        assert_eq!(view.is_mutable().unwrap(), metadata.is_mutable);
+        assert_eq!(view.edition_nonce().unwrap(), metadata.edition_nonce);
+        assert!(view.token_standard().unwrap() == metadata.token_standard.map(to_token_standard));
+
+        let collection = view.collection().unwrap();
+        assert_eq!(collection.is_some(), metadata.collection.is_some());
+        if let (Some(collection), Some(expected)) = (collection, &metadata.collection) {
+            assert_eq!(collection.verified, expected.verified as u8);
+            assert_eq!(collection.key, expected.key);
+        }
+
+        assert!(view.uses().unwrap() == metadata.uses.as_ref().map(to_uses));
+        let uses = view.uses_view().unwrap();
+        assert_eq!(uses.is_some(), metadata.uses.is_some());
+        if let (Some(uses), Some(expected)) = (uses, &metadata.uses) {
+            assert!(uses.use_method().unwrap() == to_use_method(expected.use_method));
+            assert_eq!(uses.remaining(), expected.remaining);
+            assert_eq!(uses.total(), expected.total);
+        }
+        assert!(
+            view.collection_details().unwrap()
+                == metadata
+                    .collection_details
+                    .as_ref()
+                    .map(to_collection_details)
+        );
+
+        match (
+            view.programmable_config().unwrap(),
+            &metadata.programmable_config,
+        ) {
+            (None, None) => {}
+            (
+                Some(ProgrammableConfig::V1 { rule_set }),
+                Some(mpl::ProgrammableConfig::V1 { rule_set: expected }),
+            ) => assert_eq!(rule_set, expected.as_ref()),
+            _ => panic!("programmable config doesn't match"),
+        }
+    }
+}
+
+#[test]
+fn metadata_without_newer_fields() {
+    // old accounts end right after is_mutable, everything after it reads as None
+    let mut rng = Rng::new(101);
+    let data = common::data(&mut rng);
+
+    let mut bytes = vec![Key::MetadataV1 as u8];
+    bytes.extend(rng.pubkey());
+    bytes.extend(rng.pubkey());
+    bytes.extend(borsh::to_vec(&data).unwrap());
+    bytes.extend([1, 0]);
+
+    let view = MetadataView::new(&bytes).unwrap();
+    assert!(view.primary_sale_happened().unwrap());
+    assert!(!view.is_mutable().unwrap());
+    assert_eq!(view.edition_nonce().unwrap(), None);
+    assert!(view.token_standard().unwrap().is_none());
+    assert!(view.collection().unwrap().is_none());
+    assert!(view.uses().unwrap().is_none());
+    assert!(view.uses_view().unwrap().is_none());
+    assert!(view.collection_details().unwrap().is_none());
+    assert!(view.programmable_config().unwrap().is_none());
+}
+
+/// Every getter, only checking that it doesn't panic
+fn read_everything(view: &MetadataView) {
+    let _ = view.update_authority();
+    let _ = view.mint();
+    let _ = view.name();
+    let _ = view.symbol();
+    let _ = view.uri();
+    let _ = view.seller_fee_basis_points();
+    let _ = view.creators();
+    let _ = view.primary_sale_happened();
+    let _ = view.is_mutable();
+    let _ = view.edition_nonce();
+    let _ = view.token_standard();
+    let _ = view.collection();
+    let _ = view.uses();
+    let _ = view.uses_view();
+    let _ = view.collection_details();
+    let _ = view.programmable_config();
+}
+
+#[test]
+fn metadata_truncated_or_corrupt() {
+    let mut rng = Rng::new(106);
+
+    for _ in 0..ITERATIONS / 10 {
+        let metadata = mpl::Metadata {
+            key: mpl::Key::MetadataV1,
+            update_authority: rng.pubkey(),
+            mint: rng.pubkey(),
+            data: common::data(&mut rng),
+            primary_sale_happened: rng.bool(),
+            is_mutable: rng.bool(),
+            edition_nonce: rng.option(Rng::u8),
+            token_standard: rng.option(common::token_standard),
+            collection: rng.option(common::collection),
+            uses: rng.option(common::uses),
+            collection_details: rng.option(common::collection_details),
+            programmable_config: rng.option(|rng| mpl::ProgrammableConfig::V1 {
+                rule_set: rng.option(Rng::pubkey),
+            }),
+        };
+        let mut bytes = borsh::to_vec(&metadata).unwrap();
+
+        // errors are fine, panics aren't
+        for len in 0..bytes.len() {
+            if let Ok(view) = MetadataView::new(&bytes[..len]) {
+                read_everything(&view);
+            }
+        }
+
+        let index = 1 + rng.below(bytes.len() - 1);
+        bytes[index] = rng.u8();
+        if let Ok(view) = MetadataView::new(&bytes) {
+            read_everything(&view);
+        }
+    }
+}
+
+#[test]
+fn skip_truncated() {
+    assert!(<Option<&str>>::skip_bytes(&[]).is_err());
+    // some string, whose length prefix is cut off
+    assert!(<Option<&str>>::skip_bytes(&[1, 3, 0]).is_err());
+    assert!(<&[&str]>::skip_bytes(&[]).is_err());
+    // two strings announced, the second is missing
+    assert!(<&[&str]>::skip_bytes(&[2, 0, 0, 0, 1, 0, 0, 0, b'a']).is_err());
+    assert!(<&[Option<&str>]>::skip_bytes(&[1, 0, 0, 0]).is_err());
+}
+
+#[test]
+fn metadata_wrong_key() {
+    let bytes = [Key::MasterEditionV2 as u8; 400];
+    assert!(MetadataView::new(&bytes).is_err());
+}
+
+#[test]
+fn master_edition() {
+    let mut rng = Rng::new(102);
+
+    for _ in 0..ITERATIONS {
+        let supply = rng.number();
+        let max_supply = rng.option(Rng::number);
+
+        let bytes = borsh::to_vec(&mpl::MasterEditionV2 {
+            key: mpl::Key::MasterEditionV2,
+            supply,
+            max_supply,
+        })
+        .unwrap();
+
+        let view = MasterEditionView::new(&bytes).unwrap();
+        assert!(view.key() == Key::MasterEditionV2);
+        assert_eq!(view.supply(), supply);
+        assert_eq!(view.max_supply().unwrap(), max_supply);
+        assert_eq!(
+            view.remaining_supply().unwrap(),
+            max_supply.map(|max| max.saturating_sub(supply))
+        );
+        assert_eq!(view.printing_mint().unwrap(), None);
+
+        let printing_mint = rng.pubkey();
+        let authorization_mint = rng.pubkey();
+        let bytes = borsh::to_vec(&mpl::MasterEditionV1 {
+            key: mpl::Key::MasterEditionV1,
+            supply,
+            max_supply,
+            printing_mint,
+            one_time_printing_authorization_mint: authorization_mint,
+        })
+        .unwrap();
+
+        let view = MasterEditionView::new(&bytes).unwrap();
+        assert!(view.key() == Key::MasterEditionV1);
+        assert_eq!(view.max_supply().unwrap(), max_supply);
+        assert_eq!(view.printing_mint().unwrap(), Some(&printing_mint));
+        assert_eq!(
+            view.one_time_printing_authorization_mint().unwrap(),
+            Some(&authorization_mint)
+        );
+
+        // V1 without the mints
+        assert!(MasterEditionView::new(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
+
+#[test]
+fn edition() {
+    let mut rng = Rng::new(103);
+
+    for _ in 0..ITERATIONS {
+        let parent = rng.pubkey();
+        let edition = rng.number();
+
+        let bytes = borsh::to_vec(&mpl::Edition {
+            key: mpl::Key::EditionV1,
+            parent,
+            edition,
+        })
+        .unwrap();
+        assert_eq!(bytes.len(), EditionView::LEN);
+
+        let view = EditionView::new(&bytes).unwrap();
+        assert_eq!(view.parent(), &parent);
+        assert_eq!(view.edition(), edition);
+
+        assert!(EditionView::new(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
+
+#[test]
+fn token_record() {
+    let mut rng = Rng::new(104);
+    let states = [
+        (mpl::TokenState::Unlocked, TokenState::Unlocked),
+        (mpl::TokenState::Locked, TokenState::Locked),
+        (mpl::TokenState::Listed, TokenState::Listed),
+    ];
+    let roles = [
+        (mpl::TokenDelegateRole::Sale, TokenDelegateRole::Sale),
+        (
+            mpl::TokenDelegateRole::Transfer,
+            TokenDelegateRole::Transfer,
+        ),
+        (mpl::TokenDelegateRole::Utility, TokenDelegateRole::Utility),
+        (mpl::TokenDelegateRole::Staking, TokenDelegateRole::Staking),
+        (
+            mpl::TokenDelegateRole::Standard,
+            TokenDelegateRole::Standard,
+        ),
+        (
+            mpl::TokenDelegateRole::LockedTransfer,
+            TokenDelegateRole::LockedTransfer,
+        ),
+        (
+            mpl::TokenDelegateRole::Migration,
+            TokenDelegateRole::Migration,
+        ),
+    ];
+
+    for _ in 0..ITERATIONS {
+        let bump = rng.u8();
+        let (state, expected_state) = states[rng.below(states.len())];
+        let rule_set_revision = rng.option(Rng::number);
+        let delegate = rng.option(Rng::pubkey);
+        let role = rng.option(|rng| roles[rng.below(roles.len())]);
+        let locked_transfer = rng.option(Rng::pubkey);
+
+        let mut bytes = borsh::to_vec(&mpl::TokenRecord {
+            key: mpl::Key::TokenRecord,
+            bump,
+            state,
+            rule_set_revision,
+            delegate,
+            delegate_role: role.map(|(role, _)| role),
+            locked_transfer,
+        })
+        .unwrap();
+        assert!(bytes.len() <= TokenRecordView::LEN);
+        bytes.resize(TokenRecordView::LEN, 0);
+
+        let view = TokenRecordView::new(&bytes).unwrap();
+        assert_eq!(view.bump(), bump);
+        assert!(view.state().unwrap() == expected_state);
+        assert_eq!(view.rule_set_revision().unwrap(), rule_set_revision);
+        assert_eq!(view.delegate().unwrap(), delegate.as_ref());
+        assert!(view.delegate_role().unwrap() == role.map(|(_, role)| role));
+        assert_eq!(view.locked_transfer().unwrap(), locked_transfer.as_ref());
+    }
+}
+
+#[test]
+fn token_standard_and_details_are_read_back() {
+    // V2 details keep their padding
+    let details = CollectionDetails::V2 { padding: [7; 8] };
+    let mut rng = Rng::new(105);
+
+    let metadata = mpl::Metadata {
+        key: mpl::Key::MetadataV1,
+        update_authority: rng.pubkey(),
+        mint: rng.pubkey(),
+        data: common::data(&mut rng),
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(mpl::TokenStandard::ProgrammableNonFungible),
+        collection: None,
+        uses: None,
+        collection_details: Some(mpl::CollectionDetails::V2 { padding: [7; 8] }),
+        programmable_config: None,
+    };
+    let bytes = borsh::to_vec(&metadata).unwrap();
+
+    let view = MetadataView::new(&bytes).unwrap();
+    assert!(view.token_standard().unwrap() == Some(TokenStandard::ProgrammableNonFungible));
+    assert!(view.collection_details().unwrap() == Some(details));
+}
+
+#[test]
+fn views_check_owner() {
+    let a = Accounts::build(&[TestAccount {
+        owner: SPL_TOKEN_ID,
+        data_len: 300,
+        ..Default::default()
+    }]);
+    let account = a.get(0);
+    let invalid_owner = Some(PnftError::InvalidOwner.into());
+
+    unsafe {
+        assert!(MasterEditionView::from_account_info_unchecked(account).err() == invalid_owner);
+        assert!(EditionView::from_account_info_unchecked(account).err() == invalid_owner);
+        assert!(TokenRecordView::from_account_info_unchecked(account).err() == invalid_owner);
+    }
+
+    assert!(MetadataView::from_account_info(account).err() == invalid_owner);
+    assert!(MasterEditionView::from_account_info(account).err() == invalid_owner);
+    assert!(EditionView::from_account_info(account).err() == invalid_owner);
+    assert!(TokenRecordView::from_account_info(account).err() == invalid_owner);
+}
+
+#[test]
+fn views_borrow_the_data() {
+    let a = Accounts::build(&[TestAccount {
+        owner: MPL_TOKEN_METADATA_ID,
+        data_len: TokenRecordView::LEN,
+        ..Default::default()
+    }]);
+    let account = a.get(0);
+
+    // wrong key, the data isn't left borrowed
+    assert!(
+        TokenRecordView::from_account_info(account).err() == Some(ProgramError::InvalidAccountData)
+    );
+    assert!(account.try_borrow_mut_data().is_ok());
+
+    {
+        let mut data = account.try_borrow_mut_data().unwrap();
+        data[0] = Key::TokenRecord as u8;
+        data[1] = 254;
+    }
+
+    let token_record = TokenRecordView::from_account_info(account).unwrap();
+    assert_eq!(token_record.view().bump(), 254);
+    assert!(account.try_borrow_data().is_ok());
+    assert!(account.try_borrow_mut_data().is_err());
+
+    drop(token_record);
+    assert!(account.try_borrow_mut_data().is_ok());
+}